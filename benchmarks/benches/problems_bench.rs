@@ -13,7 +13,10 @@ pub fn sat_benchmark(c: &mut Criterion) {
             &config,
             |b, config| {
                 b.iter(|| {
-                    run_sat("../data/instances/sat-3/uf100-01.cnf", *config)
+                    run_sat(
+                        "../data/instances/sat-3/uf100-01.cnf",
+                        config.clone(),
+                    )
                 })
             },
         );
@@ -33,7 +36,7 @@ pub fn algebraic_benchmark(c: &mut Criterion) {
                         run_algebraic(
                             "../data/instances/algebraic-function/\
                              algebraic-function.txt",
-                            *config,
+                            config.clone(),
                         )
                     })
                 },
@@ -51,7 +54,10 @@ pub fn radio_benchmark(c: &mut Criterion) {
             &config,
             |b, config| {
                 b.iter(|| {
-                    run_radio("../data/instances/radio/radio_1.txt", *config)
+                    run_radio(
+                        "../data/instances/radio/radio_1.txt",
+                        config.clone(),
+                    )
                 })
             },
         );
@@ -73,7 +79,7 @@ pub fn nqueens_benchmark(c: &mut Criterion) {
                             config.pop_config.dim
                         )
                         .as_str(),
-                        *config,
+                        config.clone(),
                     )
                 })
             },
@@ -88,6 +94,7 @@ fn create_sat_configs() -> Vec<(String, Config)> {
         pop_size: 100,
         pop_type: PopType::Binary,
         bounds: None,
+        initialization: None,
     };
     let mut config = Config::default();
     config.pop_config = pop_config.clone();
@@ -111,6 +118,7 @@ fn create_algebraic_configs() -> Vec<(String, Vec<Config>)> {
                 pop_size: 10 + (pop_i * 30),
                 pop_type: PopType::Binary,
                 bounds: None,
+                initialization: None,
             };
             let mut config = Config::default();
             config.pop_config = pop_config.clone();
@@ -134,6 +142,7 @@ fn create_radio_configs() -> Vec<(String, Config)> {
         pop_size: 30,
         pop_type: PopType::Binary,
         bounds: None,
+        initialization: None,
     };
     let mut config = Config::default();
     config.pop_config = pop_config.clone();
@@ -154,6 +163,7 @@ fn create_nqueens_configs() -> Vec<(String, Config)> {
             pop_size: *pop_size,
             pop_type: PopType::Permuted,
             bounds: None,
+            initialization: None,
         };
         let mut config = Config::default();
         config.crossover_method = CrossoverMethod::Cycle;
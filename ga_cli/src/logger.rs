@@ -6,12 +6,17 @@ use log4rs::{
     Config,
 };
 
-pub fn config_tracing(problem_name: &str) {
-    let file_path = format!(
-        "data/outputs/{}-{}.log",
+/// Path, without extension, shared by the outputs of this execution.
+pub fn output_path(problem_name: &str) -> String {
+    format!(
+        "data/outputs/{}-{}",
         problem_name,
         chrono::Local::now().format("%Y-%m-%d-%H-%M-%S")
-    );
+    )
+}
+
+pub fn config_tracing(output_path: &str) {
+    let file_path = format!("{output_path}.log");
 
     // Logging to log file.
     let log_file = FileAppender::builder()
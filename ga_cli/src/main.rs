@@ -1,8 +1,21 @@
-use clap::Parser;
-use genetic_framework::Framework;
-use utils::Problems;
+use std::{
+    fs, process,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+};
 
-use crate::logger::config_tracing;
+use clap::{Parser, Subcommand};
+use genetic_framework::{
+    progress::ProgressReporter, Checkpoint, Evaluation, Framework,
+};
+use loader_config::Parallelism;
+use population::Solution;
+use utils::{MetricsFormat, Problems};
+
+use crate::logger::{config_tracing, output_path};
 
 mod logger;
 mod utils;
@@ -10,6 +23,9 @@ mod utils;
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Name of the problem
     #[arg(short, long, value_enum)]
     problem_name: Option<Problems>,
@@ -21,6 +37,32 @@ struct Args {
     /// Path to the config file
     #[arg(short, long)]
     config: Option<String>,
+
+    /// Seed for reproducible runs, overrides the config seed
+    #[arg(short, long)]
+    seed: Option<u64>,
+
+    /// Write per-generation metrics in this format next to the log
+    #[arg(short, long, value_enum, global = true)]
+    metrics: Option<MetricsFormat>,
+
+    /// Execute the runs concurrently, overrides the config parallelism
+    #[arg(long, global = true)]
+    parallel_runs: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Continue the runs of an interrupted execution
+    Resume {
+        /// Path to the checkpoint file
+        checkpoint: String,
+    },
+    /// Evaluate a stored solution again
+    Evaluate {
+        /// Path to the solution file
+        solution: String,
+    },
 }
 
 fn validate_args(args: &Args) {
@@ -46,15 +88,40 @@ fn validate_args(args: &Args) {
     }
 }
 
-fn main() {
-    let args = Args::parse();
+/// Sets `interrupt` on the first Ctrl-C, exits on the second.
+fn handle_interrupts(interrupt: Arc<AtomicBool>) {
+    thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("Unable to start signal handler");
+        runtime.block_on(async {
+            tokio::signal::ctrl_c()
+                .await
+                .expect("Unable to listen for Ctrl-C");
+            eprintln!(
+                "Interrupted, stopping after the current generation. Press \
+                 Ctrl-C again to quit without a checkpoint."
+            );
+            interrupt.store(true, Ordering::Relaxed);
+            tokio::signal::ctrl_c()
+                .await
+                .expect("Unable to listen for Ctrl-C");
+            process::exit(130);
+        });
+    });
+}
+
+/// Framework for a new execution, its output path and checkpoint file.
+fn start(args: Args) -> (Framework, String, String) {
     validate_args(&args);
 
     let problem_name = match args.problem_name {
         Some(problem_name) => problem_name.to_string(),
         None => utils::ask_for_problem_name().expect("Problem not found"),
     };
-    config_tracing(&problem_name);
+    let output = output_path(&problem_name);
+    config_tracing(&output);
 
     let instance = match args.instance {
         Some(instance) => instance,
@@ -66,8 +133,137 @@ fn main() {
         None => utils::ask_for_config(&problem_name).expect("Config not found"),
     };
 
-    let (problem, config) =
-        problem_factory::problem_factory(&problem_name, &instance, &config_path);
-    let ga_framework = Framework::new(problem, config);
-    println!("{:?}", ga_framework.run());
+    let (problem, mut config) =
+        problem_factory::problem_factory(&problem_name, &instance, &config_path)
+            .unwrap_or_else(|error| {
+                eprintln!("{error}");
+                process::exit(1);
+            });
+    if args.seed.is_some() {
+        config.seed = args.seed;
+    }
+    if args.parallel_runs {
+        config.parallelism = Some(Parallelism::Outer);
+    }
+    let checkpoint_path = format!("{output}.checkpoint.json");
+    let mut ga_framework =
+        Framework::new(problem, config).unwrap_or_else(|error| {
+            eprintln!("{error}");
+            process::exit(1);
+        });
+    ga_framework.enable_checkpoints(&checkpoint_path, &problem_name, &instance);
+    ga_framework
+        .enable_solutions(&output, &problem_name, &instance)
+        .unwrap_or_else(|error| {
+            eprintln!("{error}");
+            process::exit(1);
+        });
+    (ga_framework, output, checkpoint_path)
+}
+
+/// Framework continuing the checkpointed execution, and its output path.
+fn resume(checkpoint_path: &str, parallel_runs: bool) -> (Framework, String) {
+    let mut checkpoint =
+        Checkpoint::load(checkpoint_path).unwrap_or_else(|error| {
+            eprintln!("{checkpoint_path}: {error}");
+            process::exit(1);
+        });
+    let output = output_path(&checkpoint.problem);
+    config_tracing(&output);
+    if parallel_runs {
+        checkpoint.config.parallelism = Some(Parallelism::Outer);
+    }
+    let (problem, instance) =
+        (checkpoint.problem.clone(), checkpoint.instance.clone());
+    let mut ga_framework =
+        Framework::from_checkpoint(checkpoint, checkpoint_path).unwrap_or_else(
+            |error| {
+                eprintln!("{error}");
+                process::exit(1);
+            },
+        );
+    ga_framework
+        .enable_solutions(&output, &problem, &instance)
+        .unwrap_or_else(|error| {
+            eprintln!("{error}");
+            process::exit(1);
+        });
+    (ga_framework, output)
+}
+
+/// Prints the fitness, objective and constraint of a stored solution on
+/// its problem as currently implemented. The fitness is the one runs
+/// report, the objective penalized by the static `constraint_penalty`.
+fn evaluate(solution_path: &str) {
+    let solution = Solution::load(solution_path).unwrap_or_else(|error| {
+        eprintln!("{solution_path}: {error}");
+        process::exit(1);
+    });
+    let problem =
+        problem_factory::solution_problem(&solution).unwrap_or_else(|error| {
+            eprintln!("{error}");
+            process::exit(1);
+        });
+    let decoded = problem.decode(&solution.chromosome);
+    let evaluation = Evaluation::new(&*problem, &solution.chromosome);
+    println!(
+        "Fitness: {}",
+        evaluation.fitness(solution.config.constraint_penalty)
+    );
+    println!("Objective: {}", problem.objective(&decoded));
+    println!("Constraint: {}", problem.constraint(&decoded));
+}
+
+fn main() {
+    let args = Args::parse();
+    let metrics = args.metrics;
+    let (mut ga_framework, output, checkpoint_path) = match args.command {
+        Some(Command::Resume { checkpoint }) => {
+            let (ga_framework, output) =
+                resume(&checkpoint, args.parallel_runs);
+            (ga_framework, output, checkpoint)
+        }
+        Some(Command::Evaluate { solution }) => {
+            evaluate(&solution);
+            return;
+        }
+        None => start(args),
+    };
+    let config = ga_framework.config();
+    let progress = ProgressReporter::new(config.qtd_gen, config.qtd_runs);
+    ga_framework.register_observer(progress);
+    handle_interrupts(ga_framework.interrupt_handle());
+    if let Some(format) = metrics {
+        let sink = utils::create_metrics_sink(format, &output).unwrap_or_else(
+            |error| {
+                eprintln!("{error}");
+                process::exit(1);
+            },
+        );
+        ga_framework.set_metrics_sink(sink);
+    }
+    let summary = ga_framework.run();
+    if ga_framework.interrupted() {
+        eprintln!("Resume with: ga_cli resume {checkpoint_path}");
+        process::exit(130);
+    }
+    let summary_json = serde_json::to_string_pretty(&summary)
+        .expect("Failed to convert run summary to json string");
+    fs::write(format!("{output}.summary.json"), summary_json).unwrap_or_else(
+        |error| {
+            eprintln!("Unable to write run summary: {error}");
+            process::exit(1);
+        },
+    );
+    if let Some(best) = summary.best() {
+        println!(
+            "Best run: {}, fitness: {}, objective: {}",
+            best.run,
+            best.best_value.unwrap_or(f64::NAN),
+            best.objective.unwrap_or(f64::NAN)
+        );
+        if let Some(path) = ga_framework.solution_path(best.run) {
+            println!("Solution: {path}");
+        }
+    }
 }
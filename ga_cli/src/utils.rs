@@ -2,6 +2,9 @@ use std::fs;
 
 use anyhow::Result;
 use clap::ValueEnum;
+#[cfg(feature = "parquet")]
+use genetic_framework::metrics::ParquetSink;
+use genetic_framework::metrics::{CsvSink, JsonLinesSink, MetricsSink};
 use inquire::{list_option::ListOption, Select};
 use strum::IntoEnumIterator;
 use strum_macros::{Display, EnumIter};
@@ -30,6 +33,43 @@ pub enum Problems {
     NQueens,
     #[strum(serialize = "NQUEENS-WEIGHTED")]
     NQueensWeighted,
+    #[strum(serialize = "WEIGHTED-MAX-SAT")]
+    WeightedMaxSat,
+    #[strum(serialize = "TSP")]
+    Tsp,
+}
+
+/// File format of the per-generation metrics.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum MetricsFormat {
+    Csv,
+    Jsonl,
+    Parquet,
+}
+
+/// Creates the metrics file `<output>.<format>`.
+pub fn create_metrics_sink(
+    format: MetricsFormat,
+    output: &str,
+) -> Result<Box<dyn MetricsSink + Send>> {
+    Ok(match format {
+        MetricsFormat::Csv => {
+            Box::new(CsvSink::create(format!("{output}.csv"))?)
+        }
+        MetricsFormat::Jsonl => {
+            Box::new(JsonLinesSink::create(format!("{output}.jsonl"))?)
+        }
+        #[cfg(feature = "parquet")]
+        MetricsFormat::Parquet => {
+            Box::new(ParquetSink::create(format!("{output}.parquet"))?)
+        }
+        #[cfg(not(feature = "parquet"))]
+        MetricsFormat::Parquet => {
+            return Err(anyhow::anyhow!(
+                "Parquet metrics require building with the parquet feature"
+            ))
+        }
+    })
 }
 
 pub fn ask_for_problem_name() -> Result<String> {
@@ -60,6 +100,8 @@ pub fn ask_for_instance(problem_name: &str) -> Result<String> {
                     .into_string()
                     .expect("Unable to convert to string")
             })
+            // Optimal tours are read alongside their TSP instance.
+            .filter(|path| !path.ends_with(".opt.tour"))
             .collect();
     Ok(Select::new("Which instance to run?", instances_options)
         .with_formatter(&format_path)
@@ -0,0 +1,57 @@
+use anyhow::Result;
+use population::Individual;
+use serde::{Deserialize, Serialize};
+
+use crate::Evaluation;
+
+/// State of a run once `generation` is evaluated, enough to continue it
+/// with the results it would have had uninterrupted.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RunCheckpoint {
+    pub run: usize,
+    pub generation: usize,
+    /// Generations since the best fitness last strictly improved.
+    pub stagnant_generations: usize,
+    /// Generation in which the best individual was found.
+    pub best_generation: usize,
+    pub elapsed_secs: f64,
+    /// One population, or one per island.
+    pub populations: Vec<PopulationState>,
+}
+
+/// State of one population once a generation is evaluated.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PopulationState {
+    /// Seed of the population's random stream, forked by generation.
+    pub rng_seed: u64,
+    pub individuals: Vec<Individual>,
+    /// Evaluations of `individuals`, bred from on resume.
+    pub evaluated: Vec<Evaluation>,
+    pub best_individual: Option<Individual>,
+    pub best_value: Option<f64>,
+    pub best_evaluation: Option<Evaluation>,
+    pub generations_without_improvement: usize,
+    pub evaluations: usize,
+    /// Values the fitness scaling carries between generations.
+    pub scaling: Vec<f64>,
+    /// Values the constraint handling carries between generations.
+    pub constraint_handling: Vec<f64>,
+    pub fitness_cache: Option<CacheState>,
+}
+
+/// Entries of a fitness cache, from the least to the most recently used.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CacheState {
+    pub entries: Vec<(Individual, Evaluation)>,
+    pub hits: usize,
+}
+
+/// Destination of the checkpoints of the runs.
+pub trait CheckpointSink {
+    /// Called every `checkpoint_interval` generations and when the run is
+    /// interrupted.
+    ///
+    /// # Errors
+    /// If the checkpoint cannot be written
+    fn save(&self, checkpoint: RunCheckpoint) -> Result<()>;
+}
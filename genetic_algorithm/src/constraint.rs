@@ -0,0 +1,305 @@
+use loader_config::{Config, ConstraintHandling};
+use population::{Individual, RngStream};
+use problem_factory::problem::Problem;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_DYNAMIC_PENALTY_C: f64 = 0.5;
+const DEFAULT_DYNAMIC_PENALTY_ALPHA: f64 = 2.0;
+const DEFAULT_ADAPTIVE_PENALTY_WINDOW: usize = 5;
+const DEFAULT_ADAPTIVE_PENALTY_FACTOR: f64 = 2.0;
+const DEFAULT_RANKING_PROBABILITY: f64 = 0.45;
+
+/// Normalized objective and constraint violation of an individual.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Evaluation {
+    pub objective: f64,
+    pub violation: f64,
+    pub feasible: bool,
+}
+
+impl Evaluation {
+    pub fn new(
+        problem: &(dyn Problem + Sync + Send),
+        individual: &Individual,
+    ) -> Self {
+        let decoded = problem.decode(individual);
+        Evaluation {
+            objective: problem.normed_objective(&decoded),
+            violation: problem.constraint(&decoded),
+            feasible: problem.is_feasible(&decoded),
+        }
+    }
+
+    /// The objective plus `penalty` times the violation.
+    pub fn fitness(&self, penalty: f64) -> f64 {
+        self.objective + penalty * self.violation
+    }
+}
+
+/// Maps the evaluations of a generation to the fitness its parents are
+/// selected by.
+pub trait ConstraintHandler {
+    fn fitness(
+        &mut self,
+        evaluated: &[Evaluation],
+        generation: usize,
+        rng_stream: &RngStream,
+    ) -> Vec<(usize, f64)>;
+
+    /// Values carried from one generation to the next, for checkpoints.
+    fn state(&self) -> Vec<f64> {
+        Vec::new()
+    }
+
+    /// Restores the values returned by [`ConstraintHandler::state`].
+    fn restore(&mut self, _state: &[f64]) {}
+}
+
+/// Builds the configured constraint handling, a static penalty when the
+/// config does not set one.
+pub fn from_config(
+    config: &Config,
+) -> Box<dyn ConstraintHandler + Sync + Send> {
+    let penalty = config.constraint_penalty;
+    match config
+        .constraint_handling
+        .unwrap_or(ConstraintHandling::StaticPenalty)
+    {
+        ConstraintHandling::StaticPenalty => {
+            Box::new(StaticPenalty::new(penalty))
+        }
+        ConstraintHandling::DynamicPenalty => Box::new(DynamicPenalty::new(
+            penalty,
+            config
+                .dynamic_penalty_c
+                .unwrap_or(DEFAULT_DYNAMIC_PENALTY_C),
+            config
+                .dynamic_penalty_alpha
+                .unwrap_or(DEFAULT_DYNAMIC_PENALTY_ALPHA),
+        )),
+        ConstraintHandling::AdaptivePenalty => Box::new(AdaptivePenalty::new(
+            penalty,
+            config
+                .adaptive_penalty_window
+                .unwrap_or(DEFAULT_ADAPTIVE_PENALTY_WINDOW),
+            config
+                .adaptive_penalty_factor
+                .unwrap_or(DEFAULT_ADAPTIVE_PENALTY_FACTOR),
+        )),
+        ConstraintHandling::FeasibilityRules => Box::new(FeasibilityRules {}),
+        ConstraintHandling::StochasticRanking => {
+            Box::new(StochasticRanking::new(
+                config
+                    .ranking_probability
+                    .unwrap_or(DEFAULT_RANKING_PROBABILITY),
+            ))
+        }
+    }
+}
+
+fn penalized(evaluated: &[Evaluation], penalty: f64) -> Vec<(usize, f64)> {
+    evaluated
+        .iter()
+        .map(|evaluation| evaluation.fitness(penalty))
+        .enumerate()
+        .collect()
+}
+
+pub struct StaticPenalty {
+    penalty: f64,
+}
+
+impl StaticPenalty {
+    pub fn new(penalty: f64) -> Self {
+        Self { penalty }
+    }
+}
+
+impl ConstraintHandler for StaticPenalty {
+    fn fitness(
+        &mut self,
+        evaluated: &[Evaluation],
+        _generation: usize,
+        _rng_stream: &RngStream,
+    ) -> Vec<(usize, f64)> {
+        penalized(evaluated, self.penalty)
+    }
+}
+
+/// Joines and Houck's penalty, multiplied by `(c * generation)^alpha`.
+pub struct DynamicPenalty {
+    penalty: f64,
+    c: f64,
+    alpha: f64,
+}
+
+impl DynamicPenalty {
+    pub fn new(penalty: f64, c: f64, alpha: f64) -> Self {
+        Self { penalty, c, alpha }
+    }
+}
+
+impl ConstraintHandler for DynamicPenalty {
+    fn fitness(
+        &mut self,
+        evaluated: &[Evaluation],
+        generation: usize,
+        _rng_stream: &RngStream,
+    ) -> Vec<(usize, f64)> {
+        let weight = (self.c * generation as f64).powf(self.alpha);
+        penalized(evaluated, self.penalty * weight)
+    }
+}
+
+/// Bean and Hadj-Alouane's penalty, whose weight follows the feasibility
+/// of the best individual over the last `window` generations.
+pub struct AdaptivePenalty {
+    penalty: f64,
+    window: usize,
+    factor: f64,
+    weight: f64,
+    /// Consecutive generations whose best individual was feasible, or
+    /// infeasible when negative.
+    streak: i64,
+}
+
+impl AdaptivePenalty {
+    pub fn new(penalty: f64, window: usize, factor: f64) -> Self {
+        Self {
+            penalty,
+            window: window.max(1),
+            factor,
+            weight: 1.0,
+            streak: 0,
+        }
+    }
+}
+
+impl ConstraintHandler for AdaptivePenalty {
+    fn fitness(
+        &mut self,
+        evaluated: &[Evaluation],
+        _generation: usize,
+        _rng_stream: &RngStream,
+    ) -> Vec<(usize, f64)> {
+        let penalty = self.penalty * self.weight;
+        let best = evaluated
+            .iter()
+            .max_by(|a, b| a.fitness(penalty).total_cmp(&b.fitness(penalty)));
+        if let Some(best) = best {
+            self.streak = match (best.feasible, self.streak) {
+                (true, streak) if streak >= 0 => streak + 1,
+                (true, _) => 1,
+                (false, streak) if streak <= 0 => streak - 1,
+                (false, _) => -1,
+            };
+        }
+        if self.streak.unsigned_abs() >= self.window as u64 {
+            if self.streak > 0 {
+                self.weight /= self.factor;
+            } else {
+                self.weight *= self.factor;
+            }
+            self.streak = 0;
+        }
+        penalized(evaluated, self.penalty * self.weight)
+    }
+
+    fn state(&self) -> Vec<f64> {
+        vec![self.weight, self.streak as f64]
+    }
+
+    fn restore(&mut self, state: &[f64]) {
+        if let [weight, streak] = state {
+            self.weight = *weight;
+            self.streak = *streak as i64;
+        }
+    }
+}
+
+/// Deb's feasibility rules as a fitness: the objective of feasible
+/// individuals, and the worst feasible objective minus the violation for
+/// infeasible ones, so any tournament between two individuals follows the
+/// rules.
+pub struct FeasibilityRules {}
+
+impl ConstraintHandler for FeasibilityRules {
+    fn fitness(
+        &mut self,
+        evaluated: &[Evaluation],
+        _generation: usize,
+        _rng_stream: &RngStream,
+    ) -> Vec<(usize, f64)> {
+        let worst = |feasible_only: bool| {
+            evaluated
+                .iter()
+                .filter(|evaluation| !feasible_only || evaluation.feasible)
+                .map(|evaluation| evaluation.objective)
+                .reduce(f64::min)
+        };
+        let floor = worst(true).or_else(|| worst(false)).unwrap_or(0.0);
+        evaluated
+            .iter()
+            .map(|evaluation| {
+                if evaluation.feasible {
+                    evaluation.objective
+                } else {
+                    floor - evaluation.violation
+                }
+            })
+            .enumerate()
+            .collect()
+    }
+}
+
+/// Runarsson and Yao's stochastic ranking, the fitness decreasing linearly
+/// from 1 for the first rank.
+pub struct StochasticRanking {
+    probability: f64,
+}
+
+impl StochasticRanking {
+    pub fn new(probability: f64) -> Self {
+        Self { probability }
+    }
+}
+
+impl ConstraintHandler for StochasticRanking {
+    fn fitness(
+        &mut self,
+        evaluated: &[Evaluation],
+        _generation: usize,
+        rng_stream: &RngStream,
+    ) -> Vec<(usize, f64)> {
+        let count = evaluated.len();
+        let mut rng = rng_stream.rng();
+        let mut ranking: Vec<usize> = (0..count).collect();
+        for _ in 0..count {
+            let mut swapped = false;
+            for j in 0..count.saturating_sub(1) {
+                let a = &evaluated[ranking[j]];
+                let b = &evaluated[ranking[j + 1]];
+                let by_objective = (a.feasible && b.feasible)
+                    || rng.gen::<f64>() < self.probability;
+                let swap = if by_objective {
+                    a.objective < b.objective
+                } else {
+                    a.violation > b.violation
+                };
+                if swap {
+                    ranking.swap(j, j + 1);
+                    swapped = true;
+                }
+            }
+            if !swapped {
+                break;
+            }
+        }
+        let mut fitness = vec![(0, 0.0); count];
+        for (rank, index) in ranking.into_iter().enumerate() {
+            fitness[index] = (index, (count - rank) as f64 / count as f64);
+        }
+        fitness
+    }
+}
@@ -0,0 +1,153 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    hash::{Hash, Hasher},
+    mem::discriminant,
+    num::NonZeroUsize,
+};
+
+use lru::LruCache;
+use population::Individual;
+use problem_factory::problem::Problem;
+#[cfg(not(feature = "sequential"))]
+use rayon::iter::{
+    IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator,
+};
+
+use crate::{checkpoint::CacheState, constraint::Evaluation};
+
+/// Individual compared gene by gene, real genes by their bits.
+struct CacheKey(Individual);
+
+impl Hash for CacheKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        discriminant(&self.0).hash(state);
+        match &self.0 {
+            Individual::Binary(genes) => genes.hash(state),
+            Individual::Permuted(genes) => genes.hash(state),
+            Individual::Integer(genes) => genes.hash(state),
+            Individual::Real(genes) => {
+                genes.iter().for_each(|gene| gene.to_bits().hash(state));
+            }
+        }
+    }
+}
+
+impl PartialEq for CacheKey {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Individual::Binary(a), Individual::Binary(b)) => a == b,
+            (Individual::Permuted(a), Individual::Permuted(b)) => a == b,
+            (Individual::Integer(a), Individual::Integer(b)) => a == b,
+            (Individual::Real(a), Individual::Real(b)) => {
+                a.len() == b.len()
+                    && a.iter().zip(b).all(|(a, b)| a.to_bits() == b.to_bits())
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Eq for CacheKey {}
+
+/// Bounded LRU cache of evaluations by individual.
+pub struct FitnessCache {
+    entries: LruCache<CacheKey, Evaluation>,
+    hits: usize,
+}
+
+impl FitnessCache {
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        FitnessCache {
+            entries: LruCache::new(capacity),
+            hits: 0,
+        }
+    }
+
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Cached evaluations from the least to the most recently used, and the
+    /// hits so far.
+    pub fn state(&self) -> CacheState {
+        CacheState {
+            entries: self
+                .entries
+                .iter()
+                .rev()
+                .map(|(key, &value)| (key.0.clone(), value))
+                .collect(),
+            hits: self.hits,
+        }
+    }
+
+    /// Restores the entries and hits returned by [`FitnessCache::state`].
+    pub fn restore(&mut self, state: CacheState) {
+        self.entries.clear();
+        for (individual, value) in state.entries {
+            self.entries.put(CacheKey(individual), value);
+        }
+        self.hits = state.hits;
+    }
+
+    /// Evaluation of every individual and how many of them were evaluated.
+    /// Cached individuals are not evaluated and duplicates are evaluated
+    /// once, at least `min_len` of them by each parallel task.
+    #[cfg_attr(feature = "sequential", allow(unused_variables))]
+    pub fn evaluate(
+        &mut self,
+        problem: &(dyn Problem + Sync + Send),
+        individuals: &[Individual],
+        min_len: usize,
+    ) -> (Vec<Evaluation>, usize) {
+        let mut evaluated: Vec<Option<Evaluation>> =
+            vec![None; individuals.len()];
+        let mut pending: HashMap<CacheKey, usize> = HashMap::new();
+        // Repeated misses and the index of their first occurrence.
+        let mut duplicates: Vec<(usize, usize)> = Vec::new();
+        for (i, individual) in individuals.iter().enumerate() {
+            let key = CacheKey(individual.clone());
+            if let Some(&value) = self.entries.get(&key) {
+                self.hits += 1;
+                evaluated[i] = Some(value);
+            } else {
+                match pending.entry(key) {
+                    Entry::Occupied(entry) => {
+                        duplicates.push((i, *entry.get()))
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert(i);
+                    }
+                }
+            }
+        }
+
+        let mut misses: Vec<(CacheKey, usize)> = pending.into_iter().collect();
+        misses.sort_by_key(|&(_, i)| i);
+
+        #[cfg(not(feature = "sequential"))]
+        let misses_iter = misses.par_iter().with_min_len(min_len);
+        #[cfg(feature = "sequential")]
+        let misses_iter = misses.iter();
+
+        let misses_evaluated: Vec<Evaluation> = misses_iter
+            .map(|(key, _)| Evaluation::new(problem, &key.0))
+            .collect();
+        let evaluations = misses_evaluated.len();
+        for ((key, i), value) in misses.into_iter().zip(misses_evaluated) {
+            evaluated[i] = Some(value);
+            self.entries.put(key, value);
+        }
+        for (i, first) in duplicates {
+            self.hits += 1;
+            evaluated[i] = evaluated[first];
+        }
+        (
+            evaluated
+                .into_iter()
+                .map(|value| value.expect("Every individual to be evaluated"))
+                .collect(),
+            evaluations,
+        )
+    }
+}
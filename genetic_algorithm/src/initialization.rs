@@ -0,0 +1,121 @@
+use loader_config::{InitStrategy, PopConfig};
+use population::{Individual, RngStream, Solution};
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{Stage, GA};
+
+impl GA<'_> {
+    /// Replaces the first individuals of the population, as many as the
+    /// configured fraction of it, by those of the configured strategy.
+    ///
+    /// # Panics
+    /// If a solution file cannot be loaded or the problem has no heuristic,
+    /// which [`problem_factory::check_config`] rules out
+    pub(crate) fn seed(&mut self, rng_stream: &RngStream) {
+        let config = self.config;
+        let pop_config = &config.pop_config;
+        let Some(init) = &pop_config.initialization else {
+            return;
+        };
+        let count = (init.fraction.clamp(0.0, 1.0) * pop_config.pop_size as f64)
+            .round() as usize;
+        let seeded = &mut self.population.individuals[..count];
+        match &init.strategy {
+            InitStrategy::Solutions(paths) => {
+                let chromosomes: Vec<Individual> = paths
+                    .iter()
+                    .map(|path| {
+                        Solution::load(path)
+                            .unwrap_or_else(|e| {
+                                panic!("Failed to load solution {path}: {e}")
+                            })
+                            .chromosome
+                    })
+                    .collect();
+                for (individual, chromosome) in
+                    seeded.iter_mut().zip(chromosomes.iter().cycle())
+                {
+                    individual.clone_from(chromosome);
+                }
+            }
+            InitStrategy::Heuristic => {
+                for (i, individual) in seeded.iter_mut().enumerate() {
+                    let mut rng = rng_stream.fork(i as u64).rng();
+                    *individual = self
+                        .problem
+                        .heuristic(&mut rng)
+                        .expect("Unable to retrieve heuristic individual");
+                }
+            }
+            InitStrategy::LatinHypercube => {
+                let (lower, upper) = real_bounds(pop_config);
+                let mut rng = rng_stream.rng();
+                let mut genes = vec![vec![0.0; pop_config.dim]; count];
+                for dimension in 0..pop_config.dim {
+                    let mut strata: Vec<usize> = (0..count).collect();
+                    strata.shuffle(&mut rng);
+                    for (individual, stratum) in genes.iter_mut().zip(strata) {
+                        individual[dimension] = lower
+                            + (upper - lower)
+                                * (stratum as f64 + rng.gen::<f64>())
+                                / count as f64;
+                    }
+                }
+                for (individual, genes) in seeded.iter_mut().zip(genes) {
+                    *individual = Individual::Real(genes);
+                }
+            }
+            InitStrategy::OppositionBased => {
+                self.seed_opposites(count, rng_stream);
+            }
+        }
+    }
+
+    /// Replaces each of the first `count` individuals by its opposite
+    /// within the bounds when the constraint handling ranks it higher,
+    /// both being evaluated as the population is.
+    fn seed_opposites(&mut self, count: usize, rng_stream: &RngStream) {
+        let (lower, upper) = real_bounds(&self.config.pop_config);
+        let originals = &self.population.individuals[..count];
+        let mut candidates = originals.to_vec();
+        candidates.extend(originals.iter().map(|individual| {
+            let Individual::Real(genes) = individual else {
+                unreachable!("Real populations hold real individuals");
+            };
+            Individual::Real(
+                genes.iter().map(|gene| lower + upper - gene).collect(),
+            )
+        }));
+        if self.config.repair.unwrap_or(false) {
+            candidates
+                .iter_mut()
+                .for_each(|individual| self.problem.repair(individual));
+        }
+        let evaluated = self.evaluate_individuals(&candidates);
+        // Ranking the candidates is not a generation, so it leaves the
+        // constraint handling as it was.
+        let state = self.constraint_handler.state();
+        let fitness = self.constraint_handler.fitness(
+            &evaluated,
+            0,
+            &rng_stream.fork(Stage::Ranking as u64),
+        );
+        self.constraint_handler.restore(&state);
+        let opposites = candidates.split_off(count);
+        for (i, (original, opposite)) in
+            candidates.into_iter().zip(opposites).enumerate()
+        {
+            self.population.individuals[i] =
+                if fitness[count + i].1 > fitness[i].1 {
+                    opposite
+                } else {
+                    original
+                };
+        }
+    }
+}
+
+fn real_bounds(pop_config: &PopConfig) -> (f64, f64) {
+    let bounds = pop_config.bounds.expect("Real populations require bounds");
+    (bounds.lower, bounds.upper)
+}
@@ -0,0 +1,358 @@
+use std::sync::{atomic::AtomicBool, Mutex};
+
+use loader_config::{
+    Config, EmigrantPolicy, ImmigrantPolicy, IslandConfig, MigrationTopology,
+};
+use population::{
+    CrossoverRegistry, Individual, MutationRegistry, Population, RngStream,
+};
+use problem_factory::problem::Problem;
+use rand::{seq::SliceRandom, Rng};
+use rayon::iter::{
+    IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator,
+};
+
+use crate::{
+    checkpoint::{CheckpointSink, RunCheckpoint},
+    metrics::MetricsSink,
+    min_len,
+    monitor::{Monitor, Snapshot},
+    observer::Observer,
+    Evaluation, RunResult, GA,
+};
+
+/// Island model: several [`GA`]s evolving apart and periodically exchanging
+/// individuals, reported as a single run.
+pub struct Archipelago<'a> {
+    islands: Vec<GA<'a>>,
+    island_config: IslandConfig,
+    monitor: Monitor<'a>,
+    rng_stream: RngStream,
+    /// Least islands a parallel task takes, see [`min_len`].
+    min_len: usize,
+    /// Generation the run continues from, 0 unless restored.
+    generation: usize,
+}
+
+impl<'a> Archipelago<'a> {
+    /// # Panics
+    /// If the config sets no island model or no islands, which
+    /// `Config::validate` rules out, or as [`GA::new`]
+    pub fn new(
+        problem: &'a (dyn Problem + Sync + Send),
+        config: &'a Config,
+        rng_stream: RngStream,
+        run: usize,
+        crossover_registry: &CrossoverRegistry,
+        mutation_registry: &MutationRegistry,
+    ) -> Self {
+        let island_config = config
+            .islands
+            .expect("Island model requires an island config");
+        assert!(island_config.islands > 0, "Island model requires islands");
+        let islands = (1..=island_config.islands)
+            .map(|island| {
+                GA::new(
+                    problem,
+                    config,
+                    rng_stream.fork(island as u64),
+                    run,
+                    crossover_registry,
+                    mutation_registry,
+                )
+            })
+            .collect();
+        Archipelago {
+            islands,
+            island_config,
+            monitor: Monitor::new(problem, config, run),
+            rng_stream: rng_stream.fork(0),
+            min_len: min_len(config),
+            generation: 0,
+        }
+    }
+
+    /// Records the statistics of every generation, over all the islands,
+    /// into `sink`.
+    pub fn set_metrics_sink(
+        &mut self,
+        sink: &'a Mutex<Box<dyn MetricsSink + Send>>,
+    ) {
+        self.monitor.set_metrics_sink(sink);
+    }
+
+    /// Notifies `observers` of the run's progress, over all the islands.
+    pub fn set_observers(
+        &mut self,
+        observers: &'a [Box<dyn Observer + Send + Sync>],
+    ) {
+        self.monitor.set_observers(observers);
+    }
+
+    /// Checkpoints the run, with every island, into `sink`.
+    pub fn set_checkpoint_sink(
+        &mut self,
+        sink: &'a (dyn CheckpointSink + Sync),
+    ) {
+        self.monitor.set_checkpoint_sink(sink);
+    }
+
+    /// Stops the run, checkpointing it, once `interrupt` is set.
+    pub fn set_interrupt(&mut self, interrupt: &'a AtomicBool) {
+        self.monitor.set_interrupt(interrupt);
+    }
+
+    /// Continues the run from `checkpoint` rather than from random
+    /// populations.
+    ///
+    /// # Panics
+    /// If the checkpoint does not hold one population per island
+    pub fn restore(&mut self, checkpoint: RunCheckpoint) {
+        assert!(
+            checkpoint.populations.len() == self.islands.len(),
+            "Checkpoint holds {} populations for {} islands",
+            checkpoint.populations.len(),
+            self.islands.len()
+        );
+        self.monitor.restore(&checkpoint);
+        self.generation = checkpoint.generation;
+        for (island, state) in
+            self.islands.iter_mut().zip(checkpoint.populations)
+        {
+            island.restore_population(state, checkpoint.generation);
+        }
+    }
+
+    /// Runs generations on every island until a stop condition holds for
+    /// the islands as a whole.
+    ///
+    /// # Panics
+    /// If a fitness is NaN, or the metrics or a checkpoint cannot be
+    /// written
+    pub fn run(&mut self) -> RunResult {
+        self.monitor.start();
+        self.islands
+            .par_iter_mut()
+            .with_min_len(self.min_len)
+            .for_each(GA::breed_restored);
+        let mut generation = self.generation;
+        loop {
+            generation += 1;
+            let (_, previous_best) = best(&self.islands);
+            let evaluated: Vec<(Vec<(usize, f64)>, bool)> = self
+                .islands
+                .par_iter_mut()
+                .with_min_len(self.min_len)
+                .map(|island| island.evaluate_generation(generation))
+                .collect();
+            if evaluated.iter().any(|(_, genocide)| *genocide) {
+                self.monitor.genocide(generation);
+            }
+            let mut results: Vec<Vec<(usize, f64)>> =
+                evaluated.into_iter().map(|(result, _)| result).collect();
+            let interval = self.island_config.migration_interval;
+            if interval > 0 && generation.is_multiple_of(interval) {
+                self.migrate(&mut results, generation);
+            }
+
+            let mut offset = 0;
+            let mut fitness = Vec::new();
+            for (island, result) in self.islands.iter().zip(&results) {
+                fitness.extend(
+                    result
+                        .iter()
+                        .map(|&(index, value)| (offset + index, value)),
+                );
+                offset += island.population.individuals.len();
+            }
+            let population = Population {
+                individuals: self
+                    .islands
+                    .iter()
+                    .flat_map(|island| island.population.individuals.clone())
+                    .collect(),
+            };
+            let (best_individual, best_value) = best(&self.islands);
+            let snapshot = Snapshot {
+                population: &population,
+                fitness: &fitness,
+                best_individual,
+                best_value,
+                evaluations: self
+                    .islands
+                    .iter()
+                    .map(|island| island.evaluations)
+                    .sum(),
+                cache_hits: self
+                    .islands
+                    .iter()
+                    .map(|island| island.fitness_cache.as_ref())
+                    .map(|cache| cache.map(|cache| cache.hits()))
+                    .sum(),
+            };
+            let stop_reason = self.monitor.end_generation(
+                generation,
+                previous_best,
+                &snapshot,
+            );
+            if self.monitor.checkpoint_due(generation, stop_reason) {
+                self.monitor.save_checkpoint(
+                    generation,
+                    self.islands.iter().map(GA::state).collect(),
+                );
+            }
+            if let Some(stop_reason) = stop_reason {
+                return self.monitor.finish(stop_reason, generation, &snapshot);
+            }
+            self.islands
+                .par_iter_mut()
+                .with_min_len(self.min_len)
+                .for_each(|island| island.breed(generation));
+        }
+    }
+
+    /// Sends the emigrants of every island along the topology, all of them
+    /// chosen before any island receives its immigrants.
+    fn migrate(
+        &mut self,
+        results: &mut [Vec<(usize, f64)>],
+        generation: usize,
+    ) {
+        let count = self.islands.len();
+        if count < 2 {
+            return;
+        }
+        let mut rng = self.rng_stream.fork(generation as u64).rng();
+        let emigrants: Vec<Vec<Migrant>> = self
+            .islands
+            .iter()
+            .zip(results.iter())
+            .map(|(island, result)| {
+                emigrants(
+                    island,
+                    result,
+                    self.island_config.migrants,
+                    self.island_config.emigrant_policy,
+                    &mut rng,
+                )
+            })
+            .collect();
+        let mut immigrants: Vec<Vec<Migrant>> = vec![Vec::new(); count];
+        for (source, emigrants) in emigrants.into_iter().enumerate() {
+            match self.island_config.topology {
+                MigrationTopology::Ring => {
+                    immigrants[(source + 1) % count].extend(emigrants);
+                }
+                MigrationTopology::FullyConnected => {
+                    for (target, immigrants) in
+                        immigrants.iter_mut().enumerate()
+                    {
+                        if target != source {
+                            immigrants.extend(emigrants.iter().cloned());
+                        }
+                    }
+                }
+                MigrationTopology::Random => {
+                    let target = (source + rng.gen_range(1..count)) % count;
+                    immigrants[target].extend(emigrants);
+                }
+            }
+        }
+        for ((island, result), immigrants) in self
+            .islands
+            .iter_mut()
+            .zip(results.iter_mut())
+            .zip(immigrants)
+        {
+            immigrate(
+                island,
+                result,
+                immigrants,
+                self.island_config.immigrant_policy,
+                &mut rng,
+            );
+        }
+    }
+}
+
+/// Best individual over all the islands, the first island's on ties.
+fn best<'s>(islands: &'s [GA]) -> (Option<&'s Individual>, Option<f64>) {
+    islands
+        .iter()
+        .filter(|island| island.best_individual_value.is_some())
+        .reduce(|best, island| {
+            if island.best_individual_value > best.best_individual_value {
+                island
+            } else {
+                best
+            }
+        })
+        .map_or((None, None), |island| {
+            (
+                island.best_individual.as_ref(),
+                island.best_individual_value,
+            )
+        })
+}
+
+/// Individual with its fitness and evaluation.
+type Migrant = (Individual, f64, Evaluation);
+
+/// Copies of `count` individuals of the island, with their fitness and
+/// evaluation.
+fn emigrants<R: Rng + ?Sized>(
+    island: &GA,
+    result: &[(usize, f64)],
+    count: usize,
+    policy: EmigrantPolicy,
+    rng: &mut R,
+) -> Vec<Migrant> {
+    let mut candidates: Vec<(usize, f64)> = result.to_vec();
+    match policy {
+        EmigrantPolicy::Best => {
+            candidates.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        }
+        EmigrantPolicy::Random => candidates.shuffle(rng),
+    }
+    candidates
+        .into_iter()
+        .take(count)
+        .map(|(index, value)| {
+            (
+                island.population.individuals[index].clone(),
+                value,
+                island.evaluated[index],
+            )
+        })
+        .collect()
+}
+
+/// Replaces individuals of the island by the immigrants, keeping `result`,
+/// the evaluations and the island's best individual up to date.
+fn immigrate<R: Rng + ?Sized>(
+    island: &mut GA,
+    result: &mut [(usize, f64)],
+    immigrants: Vec<Migrant>,
+    policy: ImmigrantPolicy,
+    rng: &mut R,
+) {
+    let mut slots: Vec<usize> = (0..result.len()).collect();
+    match policy {
+        ImmigrantPolicy::Worst => {
+            slots.sort_by(|&a, &b| result[a].1.total_cmp(&result[b].1));
+        }
+        ImmigrantPolicy::Random => slots.shuffle(rng),
+    }
+    for (slot, (individual, value, evaluation)) in
+        slots.into_iter().zip(immigrants)
+    {
+        if island.best_individual_value.is_none_or(|best| value > best) {
+            island.best_individual_value = Some(value);
+            island.best_individual = Some(individual.clone());
+            island.best_evaluation = Some(evaluation);
+        }
+        island.population.individuals[result[slot].0] = individual;
+        island.evaluated[result[slot].0] = evaluation;
+        result[slot].1 = value;
+    }
+}
@@ -1,76 +1,331 @@
 #[cfg(feature = "sequential")]
 use std::iter::once;
+use std::{
+    num::NonZeroUsize,
+    sync::{atomic::AtomicBool, Mutex},
+    time::Duration,
+};
 
 #[cfg(not(feature = "sequential"))]
 use rayon::iter::{
-    once, IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator,
+    once, IndexedParallelIterator, IntoParallelRefIterator,
+    IntoParallelRefMutIterator, ParallelIterator,
 };
 
+pub mod checkpoint;
+mod constraint;
+mod fitness_cache;
+mod initialization;
+mod island;
+pub mod metrics;
+mod monitor;
+pub mod observer;
+#[cfg(feature = "progress")]
+pub mod progress;
+mod scaling;
 mod selection;
+mod termination;
 
-use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
-use loader_config::Config;
-use log::info;
-use population::{Individual, Population};
+use checkpoint::{CheckpointSink, PopulationState, RunCheckpoint};
+use constraint::ConstraintHandler;
+pub use constraint::Evaluation;
+use fitness_cache::FitnessCache;
+pub use island::Archipelago;
+use loader_config::{Config, Parallelism};
+use metrics::MetricsSink;
+use monitor::{Monitor, Snapshot};
+use observer::Observer;
+use population::{
+    Crossover, CrossoverRegistry, Individual, Mutation, MutationRegistry,
+    Population, RngStream,
+};
 use problem_factory::problem::Problem;
-use rand::{seq::SliceRandom, thread_rng, Rng};
-use selection::{RouletteWheel, Selection, Tournament};
+use rand::{seq::SliceRandom, Rng};
+use scaling::Scaling;
+use selection::Selection;
+pub use termination::StopReason;
+
+/// Keys forking a generation's random stream into one stream per stage.
+#[derive(Clone, Copy)]
+enum Stage {
+    Initialization,
+    Genocide,
+    Selection,
+    Crossover,
+    Mutation,
+    GenerationGap,
+    Seeding,
+    Ranking,
+}
+
+/// Least items a parallel task takes: all of them when the runs execute
+/// concurrently, so that each run evaluates and breeds on its own thread
+/// rather than competing with the other runs for the cores.
+pub(crate) fn min_len(config: &Config) -> usize {
+    match config.parallelism {
+        Some(Parallelism::Outer) => usize::MAX,
+        Some(Parallelism::Inner) | None => 1,
+    }
+}
+
+/// Outcome of a run.
+#[derive(Clone, Debug)]
+pub struct RunResult {
+    pub best_individual: Option<Individual>,
+    pub best_value: Option<f64>,
+    pub stop_reason: StopReason,
+    /// Individuals decoded and evaluated, cache hits excluded.
+    pub evaluations: usize,
+    /// Generation in which the best individual was found.
+    pub best_generation: usize,
+    pub generations: usize,
+    pub elapsed: Duration,
+}
 
 pub struct GA<'a> {
     config: &'a Config,
     problem: &'a (dyn Problem + Sync + Send),
     population: Population,
+    /// Evaluations of `population`, by index.
+    evaluated: Vec<Evaluation>,
     best_individual: Option<Individual>,
     best_individual_value: Option<f64>,
-    multi_progress_bar: &'a MultiProgress,
+    best_evaluation: Option<Evaluation>,
     generations_without_improvement: usize,
-    selection_method: Box<dyn selection::Selection + Sync + Send>,
+    evaluations: usize,
+    fitness_cache: Option<FitnessCache>,
+    monitor: Monitor<'a>,
+    selection_method: Box<dyn Selection + Sync + Send>,
+    scaling: Box<dyn Scaling + Sync + Send>,
+    constraint_handler: Box<dyn ConstraintHandler + Sync + Send>,
+    crossover_method: Box<dyn Crossover + Sync + Send>,
+    mutation_method: Box<dyn Mutation + Sync + Send>,
+    rng_stream: RngStream,
+    /// Least individuals a parallel task takes, see [`min_len`].
+    min_len: usize,
+    /// Generation the run continues from, 0 unless restored.
+    generation: usize,
+    /// Whether the population was restored from a checkpoint and is not
+    /// bred yet.
+    restored: bool,
 }
 
 impl<'a> GA<'a> {
+    /// The config is expected to suit the problem, as checked by
+    /// [`problem_factory::check_config`].
+    ///
+    /// # Panics
+    /// If no crossover or mutation is registered under the configured
+    /// method's name
     pub fn new(
         problem: &'a (dyn Problem + Sync + Send),
         config: &'a Config,
-        multi_progress_bar: &'a MultiProgress,
+        rng_stream: RngStream,
+        run: usize,
+        crossover_registry: &CrossoverRegistry,
+        mutation_registry: &MutationRegistry,
     ) -> Self {
         let population = Population::new(
-            config.pop_config.pop_size,
-            config.pop_config.dim,
-            &config.pop_config.pop_type,
+            &config.pop_config,
+            &rng_stream.fork(0).fork(Stage::Initialization as u64),
         );
-        let selection_method: Box<dyn Selection + Sync + Send> =
-            match config.selection_method {
-                loader_config::SelectionMethod::Roulette => {
-                    Box::new(RouletteWheel::new())
-                }
-                loader_config::SelectionMethod::Tournament => {
-                    Box::new(Tournament::new(config.kp))
-                }
-            };
-        GA {
+        let selection_method = selection::from_config(config);
+        let scaling = scaling::from_config(config);
+        let constraint_handler = constraint::from_config(config);
+        let crossover_method =
+            crossover_registry.build(config).unwrap_or_else(|| {
+                panic!(
+                    "Crossover method {} is not registered",
+                    config.crossover_method.name()
+                )
+            });
+        let mutation_method =
+            mutation_registry.build(config).unwrap_or_else(|| {
+                panic!(
+                    "Mutation method {} is not registered",
+                    config
+                        .mutation_method
+                        .as_ref()
+                        .map_or("default", |method| method.name())
+                )
+            });
+        let seeding_stream = rng_stream.fork(0).fork(Stage::Seeding as u64);
+        let mut ga = GA {
             problem,
             config,
             population,
-            multi_progress_bar,
+            evaluated: Vec::new(),
             best_individual: None,
             best_individual_value: None,
+            best_evaluation: None,
             generations_without_improvement: 0,
+            evaluations: 0,
+            fitness_cache: config
+                .fitness_cache_size
+                .and_then(NonZeroUsize::new)
+                .map(FitnessCache::new),
+            monitor: Monitor::new(problem, config, run),
             selection_method,
+            scaling,
+            constraint_handler,
+            crossover_method,
+            mutation_method,
+            rng_stream,
+            min_len: min_len(config),
+            generation: 0,
+            restored: false,
+        };
+        ga.seed(&seeding_stream);
+        ga
+    }
+
+    /// Records the statistics of every generation into `sink`.
+    pub fn set_metrics_sink(
+        &mut self,
+        sink: &'a Mutex<Box<dyn MetricsSink + Send>>,
+    ) {
+        self.monitor.set_metrics_sink(sink);
+    }
+
+    /// Notifies `observers` of the run's progress.
+    pub fn set_observers(
+        &mut self,
+        observers: &'a [Box<dyn Observer + Send + Sync>],
+    ) {
+        self.monitor.set_observers(observers);
+    }
+
+    /// Checkpoints the run into `sink`.
+    pub fn set_checkpoint_sink(
+        &mut self,
+        sink: &'a (dyn CheckpointSink + Sync),
+    ) {
+        self.monitor.set_checkpoint_sink(sink);
+    }
+
+    /// Stops the run, checkpointing it, once `interrupt` is set.
+    pub fn set_interrupt(&mut self, interrupt: &'a AtomicBool) {
+        self.monitor.set_interrupt(interrupt);
+    }
+
+    /// Continues the run from `checkpoint` rather than from a random
+    /// population.
+    ///
+    /// # Panics
+    /// If the checkpoint does not hold exactly one population
+    pub fn restore(&mut self, mut checkpoint: RunCheckpoint) {
+        assert!(
+            checkpoint.populations.len() == 1,
+            "Checkpoint holds {} populations",
+            checkpoint.populations.len()
+        );
+        self.monitor.restore(&checkpoint);
+        let state = checkpoint
+            .populations
+            .pop()
+            .expect("Unable to retrieve population state");
+        self.restore_population(state, checkpoint.generation);
+    }
+
+    pub(crate) fn restore_population(
+        &mut self,
+        state: PopulationState,
+        generation: usize,
+    ) {
+        self.rng_stream = RngStream::new(state.rng_seed);
+        self.population = Population {
+            individuals: state.individuals,
+        };
+        self.evaluated = state.evaluated;
+        self.best_individual = state.best_individual;
+        self.best_individual_value = state.best_value;
+        self.best_evaluation = state.best_evaluation;
+        self.generations_without_improvement =
+            state.generations_without_improvement;
+        self.evaluations = state.evaluations;
+        self.scaling.restore(&state.scaling);
+        self.constraint_handler.restore(&state.constraint_handling);
+        if let (Some(cache), Some(cache_state)) =
+            (&mut self.fitness_cache, state.fitness_cache)
+        {
+            cache.restore(cache_state);
         }
+        self.generation = generation;
+        self.restored = true;
+    }
+
+    /// State of the population once evaluated.
+    pub(crate) fn state(&self) -> PopulationState {
+        PopulationState {
+            rng_seed: self.rng_stream.seed(),
+            individuals: self.population.individuals.clone(),
+            evaluated: self.evaluated.clone(),
+            best_individual: self.best_individual.clone(),
+            best_value: self.best_individual_value,
+            best_evaluation: self.best_evaluation,
+            generations_without_improvement: self
+                .generations_without_improvement,
+            evaluations: self.evaluations,
+            scaling: self.scaling.state(),
+            constraint_handling: self.constraint_handler.state(),
+            fitness_cache: self.fitness_cache.as_ref().map(FitnessCache::state),
+        }
+    }
+
+    /// Evaluates the population, repaired first when configured, returning
+    /// its static penalty fitness.
+    fn evaluate(&mut self) -> Vec<(usize, f64)> {
+        if self.config.repair.unwrap_or(false) {
+            self.repair();
+        }
+        let population = std::mem::take(&mut self.population.individuals);
+        self.evaluated = self.evaluate_individuals(&population);
+        self.population.individuals = population;
+        self.evaluated
+            .iter()
+            .map(|evaluation| {
+                evaluation.fitness(self.config.constraint_penalty)
+            })
+            .enumerate()
+            .collect()
     }
 
-    fn evaluate(&self) -> Vec<(usize, f64)> {
-        let population = &self.population.individuals;
+    /// Evaluates `individuals` through the fitness cache, if any, counting
+    /// the evaluations.
+    fn evaluate_individuals(
+        &mut self,
+        individuals: &[Individual],
+    ) -> Vec<Evaluation> {
+        if let Some(cache) = &mut self.fitness_cache {
+            let (evaluated, evaluations) =
+                cache.evaluate(self.problem, individuals, self.min_len);
+            self.evaluations += evaluations;
+            return evaluated;
+        }
+        #[cfg(not(feature = "sequential"))]
+        let individuals_iter =
+            individuals.par_iter().with_min_len(self.min_len);
+        #[cfg(feature = "sequential")]
+        let individuals_iter = individuals.iter();
 
+        let evaluated: Vec<Evaluation> = individuals_iter
+            .map(|individual| Evaluation::new(self.problem, individual))
+            .collect();
+        self.evaluations += evaluated.len();
+        evaluated
+    }
+
+    fn repair(&mut self) {
         #[cfg(not(feature = "sequential"))]
-        let population_iter = population.par_iter();
+        let population_iter = self
+            .population
+            .individuals
+            .par_iter_mut()
+            .with_min_len(self.min_len);
         #[cfg(feature = "sequential")]
-        let population_iter = population.iter();
+        let population_iter = self.population.individuals.iter_mut();
 
-        population_iter
-            .enumerate()
-            .map(|(i, individual)| (i, self.problem.fitness(individual)))
-            .collect()
+        population_iter.for_each(|individual| self.problem.repair(individual));
     }
 
     fn update_best(&mut self, result: &[(usize, f64)]) -> Vec<(usize, f64)> {
@@ -95,6 +350,8 @@ impl<'a> GA<'a> {
                 self.best_individual = Some(
                     self.population.individuals[*best_individual_index].clone(),
                 );
+                self.best_evaluation =
+                    Some(self.evaluated[*best_individual_index]);
             } else {
                 self.generations_without_improvement += 1;
                 if self.config.elitism {
@@ -103,6 +360,9 @@ impl<'a> GA<'a> {
                         .as_ref()
                         .expect("Unable to retrieve best individual")
                         .clone();
+                    self.evaluated[*worst_individual_index] = self
+                        .best_evaluation
+                        .expect("Unable to retrieve best evaluation");
                     new_result = new_result
                         .iter()
                         .map(|tuple| {
@@ -125,20 +385,19 @@ impl<'a> GA<'a> {
             self.best_individual = Some(
                 self.population.individuals[*best_individual_index].clone(),
             );
+            self.best_evaluation = Some(self.evaluated[*best_individual_index]);
         }
         new_result
     }
 
-    fn genocide(&mut self) -> Vec<(usize, f64)> {
+    fn genocide(&mut self, rng_stream: &RngStream) -> Vec<(usize, f64)> {
         self.generations_without_improvement = 0;
-        let new_population = Population::new(
-            self.config.pop_config.pop_size,
-            self.config.pop_config.dim,
-            &self.config.pop_config.pop_type,
-        );
+        let rng_stream = rng_stream.fork(Stage::Genocide as u64);
+        let new_population =
+            Population::new(&self.config.pop_config, &rng_stream);
         let mut indexes =
             (0..self.config.pop_config.pop_size).collect::<Vec<usize>>();
-        let mut rng = thread_rng();
+        let mut rng = rng_stream.rng();
         indexes.shuffle(&mut rng);
         indexes
             .iter()
@@ -152,27 +411,43 @@ impl<'a> GA<'a> {
         self.update_best(&result)
     }
 
-    fn selection(&self, result: &[(usize, f64)]) -> Vec<(usize, usize)> {
-        self.selection_method.select(result)
+    fn selection(
+        &self,
+        result: &[(usize, f64)],
+        rng_stream: &RngStream,
+    ) -> Vec<(usize, usize)> {
+        self.selection_method
+            .select(result, &rng_stream.fork(Stage::Selection as u64))
     }
 
-    fn crossover(&self, mating_pool: &[(usize, usize)]) -> Population {
+    fn crossover(
+        &self,
+        mating_pool: &[(usize, usize)],
+        rng_stream: &RngStream,
+    ) -> Population {
         #[cfg(not(feature = "sequential"))]
-        let mating_pool_iter = mating_pool.par_iter();
+        let mating_pool_iter =
+            mating_pool.par_iter().with_min_len(self.min_len);
         #[cfg(feature = "sequential")]
         let mating_pool_iter = mating_pool.iter();
 
         let crossover_chance = self.config.crossover_chance;
-        let couples_mapped = mating_pool_iter.map(|(parent1, parent2)| {
-            let mut rng = rand::thread_rng();
-            let crossover = rng.gen::<f64>();
-            let child1: &Individual = &self.population.individuals[*parent1];
-            let child2: &Individual = &self.population.individuals[*parent2];
-            if crossover <= crossover_chance {
-                return child1.crossover(child2, &self.config.crossover_method);
-            }
-            (child1.clone(), child2.clone())
-        });
+        let rng_stream = rng_stream.fork(Stage::Crossover as u64);
+        let couples_mapped =
+            mating_pool_iter.enumerate().map(|(i, (parent1, parent2))| {
+                let mut rng = rng_stream.fork(i as u64).rng();
+                let crossover = rng.gen::<f64>();
+                let child1: &Individual =
+                    &self.population.individuals[*parent1];
+                let child2: &Individual =
+                    &self.population.individuals[*parent2];
+                if crossover <= crossover_chance {
+                    return self
+                        .crossover_method
+                        .crossover(child1, child2, &mut rng);
+                }
+                (child1.clone(), child2.clone())
+            });
         Population {
             individuals: couples_mapped
                 .flat_map(|tuple| once(tuple.0).chain(once(tuple.1)))
@@ -180,113 +455,36 @@ impl<'a> GA<'a> {
         }
     }
 
-    fn mutation(&self, new_population: &Population) -> Population {
+    fn mutation(
+        &self,
+        new_population: &Population,
+        rng_stream: &RngStream,
+    ) -> Population {
         #[cfg(not(feature = "sequential"))]
-        let individuals_iter = new_population.individuals.par_iter();
+        let individuals_iter = new_population
+            .individuals
+            .par_iter()
+            .with_min_len(self.min_len);
         #[cfg(feature = "sequential")]
         let individuals_iter = new_population.individuals.iter();
 
-        let mutation_chance = self.config.mutation_chance;
-        let mutated_population = individuals_iter
-            .map(|individual| individual.mutate(mutation_chance));
+        let rng_stream = rng_stream.fork(Stage::Mutation as u64);
+        let mutated_population =
+            individuals_iter.enumerate().map(|(i, individual)| {
+                let mut rng = rng_stream.fork(i as u64).rng();
+                self.mutation_method.mutate(individual, &mut rng)
+            });
 
         Population {
             individuals: mutated_population.collect(),
         }
     }
 
-    fn log_run_result(&self) {
-        match &self.best_individual {
-            Some(best_individual) => {
-                info!("Best Individual: {}", best_individual.clone());
-                info!(
-                    "Best Individual Value: {}",
-                    self.best_individual_value
-                        .expect("Unable to retrieve best individual value")
-                );
-                info!(
-                    "Best Individual Value Decoded: {}",
-                    self.problem
-                        .objective(&self.problem.decode(best_individual))
-                );
-                info!(
-                    "Best Individual Decoded: {:?}",
-                    self.problem.decode(best_individual)
-                );
-                info!(
-                    "Best Individual Constraint: {}",
-                    self.problem
-                        .constraint(&self.problem.decode(best_individual))
-                );
-            }
-            None => {}
-        };
-    }
-
-    fn log_generation(&self, result: &[(usize, f64)], generation: usize) {
-        let result_mapped = result.iter().map(|(_, value)| value);
-        info!(
-            "State Individual: {} {} {} {} {}",
-            generation,
-            self.best_individual_value
-                .expect("Unable to retrieve best individual value"),
-            result_mapped
-                .clone()
-                .max_by(|a, b| a.total_cmp(b))
-                .unwrap_or(&0.0),
-            result_mapped.clone().sum::<f64>() / result.len() as f64,
-            result_mapped
-                .clone()
-                .min_by(|a, b| a.total_cmp(b))
-                .unwrap_or(&0.0),
-        );
-    }
-
-    fn linear_escalation(
-        &self,
-        result: &[(usize, f64)],
-        generation: usize,
-    ) -> Vec<(usize, f64)> {
-        let total_generations: f64 = self.config.qtd_gen as f64;
-        let generation = generation as f64;
-        let c = if generation < total_generations * 0.8 {
-            1.2 + (((2.0 - 1.2) / (total_generations * 0.8)) * (generation))
-        } else {
-            2.0
-        };
-        let min = *result
-            .par_iter()
-            .map(|(_, value)| value)
-            .min_by(|a, b| a.total_cmp(b))
-            .expect("Failed to get min value.");
-        let max = *result
-            .par_iter()
-            .map(|(_, value)| value)
-            .max_by(|a, b| a.total_cmp(b))
-            .expect("Failed to get max value.");
-        let average = result.par_iter().map(|(_, value)| value).sum::<f64>()
-            / result.len() as f64;
-        let (alpha, beta) = if min > (c * average - max) / (c - 1.0) {
-            (
-                average * (c - 1.0) / (max - average),
-                average * (max - c * average) / (max - average),
-            )
-        } else {
-            (
-                average / (average - min),
-                (-min * average) / (average - min),
-            )
-        };
-        result
-            .par_iter()
-            .map(|(index, value)| (*index, alpha * value + beta))
-            .collect()
-    }
-
     fn generation_gap(
         &self,
         new_population: &Population,
         generation: usize,
+        rng_stream: &RngStream,
     ) -> Population {
         let total_generations: f64 = self.config.qtd_gen as f64;
         let generation: f64 = generation as f64;
@@ -301,10 +499,13 @@ impl<'a> GA<'a> {
         let new_population_iter = new_population
             .individuals
             .par_iter()
-            .zip(self.population.individuals.par_iter());
+            .zip(self.population.individuals.par_iter())
+            .with_min_len(self.min_len);
+        let rng_stream = rng_stream.fork(Stage::GenerationGap as u64);
         let new_population = new_population_iter
-            .map(|(new_individual, old_individual)| {
-                let mut rng = rand::thread_rng();
+            .enumerate()
+            .map(|(i, (new_individual, old_individual))| {
+                let mut rng = rng_stream.fork(i as u64).rng();
                 if rng.gen::<f64>() < proportion {
                     new_individual.clone()
                 } else {
@@ -317,49 +518,93 @@ impl<'a> GA<'a> {
         }
     }
 
+    /// The fitness after a genocide if one was due, `None` otherwise.
     fn check_genocide(
         &mut self,
-        new_result: &[(usize, f64)],
-    ) -> Vec<(usize, f64)> {
-        if self.generations_without_improvement
-            >= self.config.generations_to_genocide
-        {
-            self.genocide()
-        } else {
-            new_result.to_vec()
+        rng_stream: &RngStream,
+    ) -> Option<Vec<(usize, f64)>> {
+        (self.generations_without_improvement
+            >= self.config.generations_to_genocide)
+            .then(|| self.genocide(rng_stream))
+    }
+
+    /// Evaluates the current population, returning its fitness and whether
+    /// a genocide took place.
+    pub(crate) fn evaluate_generation(
+        &mut self,
+        generation: usize,
+    ) -> (Vec<(usize, f64)>, bool) {
+        let rng_stream = self.rng_stream.fork(generation as u64);
+        let result = self.evaluate();
+        let new_result = self.update_best(&result);
+        match self.check_genocide(&rng_stream) {
+            Some(newer_result) => (newer_result, true),
+            None => (new_result, false),
+        }
+    }
+
+    /// Breeds the population restored from a checkpoint, if any.
+    pub(crate) fn breed_restored(&mut self) {
+        if self.restored {
+            self.restored = false;
+            self.breed(self.generation);
         }
     }
 
+    /// Replaces the population by its offspring, selected by the fitness
+    /// the constraint handling gives its evaluations.
+    pub(crate) fn breed(&mut self, generation: usize) {
+        let rng_stream = self.rng_stream.fork(generation as u64);
+        let result = self.constraint_handler.fitness(
+            &self.evaluated,
+            generation,
+            &rng_stream.fork(Stage::Ranking as u64),
+        );
+        let scaled_result = self.scaling.scale(&result, generation);
+        let mating_pool = self.selection(&scaled_result, &rng_stream);
+        let mut new_population = self.crossover(&mating_pool, &rng_stream);
+        new_population = self.mutation(&new_population, &rng_stream);
+
+        self.population =
+            self.generation_gap(&new_population, generation, &rng_stream);
+    }
+
+    /// Runs generations until a stop condition holds.
+    ///
     /// # Panics
-    /// If I did shit
-    pub fn run(&mut self) -> (Option<Individual>, Option<f64>) {
-        let sty = ProgressStyle::with_template(
-            "[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}",
-        )
-        .expect("Failed to build progress bar template");
-        let pb = self
-            .multi_progress_bar
-            .add(ProgressBar::new(self.config.qtd_gen as u64));
-        pb.set_style(sty);
-
-        for generation in 1..=self.config.qtd_gen {
-            let result = self.evaluate();
-            let new_result = self.update_best(&result);
-            let newer_result = self.check_genocide(&new_result);
-
-            self.log_generation(&newer_result, generation);
-            let scaled_result =
-                self.linear_escalation(&newer_result, generation);
-            let mating_pool = self.selection(&scaled_result);
-            let mut new_population = self.crossover(&mating_pool);
-            new_population = self.mutation(&new_population);
-
-            self.population = self.generation_gap(&new_population, generation);
-
-            pb.inc(1);
+    /// If a fitness is NaN, or the metrics or a checkpoint cannot be
+    /// written
+    pub fn run(&mut self) -> RunResult {
+        self.monitor.start();
+        self.breed_restored();
+        let mut generation = self.generation;
+        loop {
+            generation += 1;
+            let previous_best = self.best_individual_value;
+            let (result, genocide) = self.evaluate_generation(generation);
+            if genocide {
+                self.monitor.genocide(generation);
+            }
+            let snapshot = Snapshot {
+                population: &self.population,
+                fitness: &result,
+                best_individual: self.best_individual.as_ref(),
+                best_value: self.best_individual_value,
+                evaluations: self.evaluations,
+                cache_hits: self.fitness_cache.as_ref().map(FitnessCache::hits),
+            };
+            let stop_reason = self.monitor.end_generation(
+                generation,
+                previous_best,
+                &snapshot,
+            );
+            if self.monitor.checkpoint_due(generation, stop_reason) {
+                self.monitor.save_checkpoint(generation, vec![self.state()]);
+            }
+            if let Some(stop_reason) = stop_reason {
+                return self.monitor.finish(stop_reason, generation, &snapshot);
+            }
+            self.breed(generation);
         }
-        self.log_run_result();
-        pb.finish_with_message(format!("Run completed:  {}", self.best_individual_value.unwrap_or(-1.0)));
-        (self.best_individual.clone(), self.best_individual_value)
     }
 }
@@ -0,0 +1,221 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use anyhow::Result;
+use serde::Serialize;
+
+/// Statistics of one generation, as written by a [`MetricsSink`].
+#[derive(Clone, Debug, Serialize)]
+pub struct GenerationMetrics {
+    pub run: usize,
+    pub generation: usize,
+    /// Best fitness found by the run so far.
+    pub best_so_far: f64,
+    pub best: f64,
+    pub mean: f64,
+    pub worst: f64,
+    pub std_dev: f64,
+    /// See [`population::Population::diversity`].
+    pub diversity: f64,
+    /// Individuals decoded and evaluated by the run so far.
+    pub evaluations: usize,
+    pub elapsed_secs: f64,
+}
+
+/// Destination of the per-generation metrics.
+pub trait MetricsSink {
+    /// # Errors
+    /// If the record cannot be written
+    fn record(&mut self, metrics: &GenerationMetrics) -> Result<()>;
+
+    /// Flushes the records, called once after every run.
+    ///
+    /// # Errors
+    /// If the records cannot be written
+    fn finish(&mut self) -> Result<()>;
+}
+
+/// Comma separated values with a header row.
+pub struct CsvSink {
+    writer: BufWriter<File>,
+}
+
+impl CsvSink {
+    /// # Errors
+    /// If the file cannot be created
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(
+            writer,
+            "run,generation,best_so_far,best,mean,worst,std_dev,diversity,\
+             evaluations,elapsed_secs"
+        )?;
+        Ok(CsvSink { writer })
+    }
+}
+
+impl MetricsSink for CsvSink {
+    fn record(&mut self, metrics: &GenerationMetrics) -> Result<()> {
+        writeln!(
+            self.writer,
+            "{},{},{},{},{},{},{},{},{},{}",
+            metrics.run,
+            metrics.generation,
+            metrics.best_so_far,
+            metrics.best,
+            metrics.mean,
+            metrics.worst,
+            metrics.std_dev,
+            metrics.diversity,
+            metrics.evaluations,
+            metrics.elapsed_secs
+        )?;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// One JSON object per line.
+pub struct JsonLinesSink {
+    writer: BufWriter<File>,
+}
+
+impl JsonLinesSink {
+    /// # Errors
+    /// If the file cannot be created
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(JsonLinesSink {
+            writer: BufWriter::new(File::create(path)?),
+        })
+    }
+}
+
+impl MetricsSink for JsonLinesSink {
+    fn record(&mut self, metrics: &GenerationMetrics) -> Result<()> {
+        serde_json::to_writer(&mut self.writer, metrics)?;
+        writeln!(self.writer)?;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "parquet")]
+pub use parquet_sink::ParquetSink;
+
+#[cfg(feature = "parquet")]
+mod parquet_sink {
+    use std::{fs::File, path::Path, sync::Arc};
+
+    use anyhow::{anyhow, Result};
+    use parquet::{
+        data_type::{DoubleType, Int64Type},
+        file::{properties::WriterProperties, writer::SerializedFileWriter},
+        schema::parser::parse_message_type,
+    };
+
+    use super::{GenerationMetrics, MetricsSink};
+
+    enum Column {
+        Int64(fn(&GenerationMetrics) -> i64),
+        Double(fn(&GenerationMetrics) -> f64),
+    }
+
+    fn columns() -> Vec<(&'static str, Column)> {
+        vec![
+            ("run", Column::Int64(|m| m.run as i64)),
+            ("generation", Column::Int64(|m| m.generation as i64)),
+            ("best_so_far", Column::Double(|m| m.best_so_far)),
+            ("best", Column::Double(|m| m.best)),
+            ("mean", Column::Double(|m| m.mean)),
+            ("worst", Column::Double(|m| m.worst)),
+            ("std_dev", Column::Double(|m| m.std_dev)),
+            ("diversity", Column::Double(|m| m.diversity)),
+            ("evaluations", Column::Int64(|m| m.evaluations as i64)),
+            ("elapsed_secs", Column::Double(|m| m.elapsed_secs)),
+        ]
+    }
+
+    /// Parquet file of a single row group, written when the runs finish.
+    pub struct ParquetSink {
+        file: Option<File>,
+        rows: Vec<GenerationMetrics>,
+    }
+
+    impl ParquetSink {
+        /// # Errors
+        /// If the file cannot be created
+        pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+            Ok(ParquetSink {
+                file: Some(File::create(path)?),
+                rows: Vec::new(),
+            })
+        }
+    }
+
+    impl MetricsSink for ParquetSink {
+        fn record(&mut self, metrics: &GenerationMetrics) -> Result<()> {
+            self.rows.push(metrics.clone());
+            Ok(())
+        }
+
+        fn finish(&mut self) -> Result<()> {
+            let Some(file) = self.file.take() else {
+                return Ok(());
+            };
+            let columns = columns();
+            let fields: Vec<String> = columns
+                .iter()
+                .map(|(name, column)| match column {
+                    Column::Int64(_) => format!("REQUIRED INT64 {name};"),
+                    Column::Double(_) => format!("REQUIRED DOUBLE {name};"),
+                })
+                .collect();
+            let schema = parse_message_type(&format!(
+                "message generation_metrics {{ {} }}",
+                fields.join(" ")
+            ))?;
+            let mut writer = SerializedFileWriter::new(
+                file,
+                Arc::new(schema),
+                Arc::new(WriterProperties::builder().build()),
+            )?;
+            let mut row_group = writer.next_row_group()?;
+            for (name, column) in &columns {
+                let mut column_writer = row_group
+                    .next_column()?
+                    .ok_or_else(|| anyhow!("Missing parquet column {name}"))?;
+                match column {
+                    Column::Int64(value) => {
+                        let values: Vec<i64> =
+                            self.rows.iter().map(value).collect();
+                        column_writer
+                            .typed::<Int64Type>()
+                            .write_batch(&values, None, None)?;
+                    }
+                    Column::Double(value) => {
+                        let values: Vec<f64> =
+                            self.rows.iter().map(value).collect();
+                        column_writer
+                            .typed::<DoubleType>()
+                            .write_batch(&values, None, None)?;
+                    }
+                }
+                column_writer.close()?;
+            }
+            row_group.close()?;
+            writer.close()?;
+            Ok(())
+        }
+    }
+}
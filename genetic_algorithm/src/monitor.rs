@@ -0,0 +1,319 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use loader_config::Config;
+use log::info;
+use population::{Individual, Population};
+use problem_factory::problem::Problem;
+
+use crate::{
+    checkpoint::{CheckpointSink, PopulationState, RunCheckpoint},
+    metrics::{GenerationMetrics, MetricsSink},
+    observer::Observer,
+    termination::{self, RunState, Termination},
+    RunResult, StopReason,
+};
+
+/// State of a run once a generation is evaluated.
+pub(crate) struct Snapshot<'s> {
+    pub population: &'s Population,
+    pub fitness: &'s [(usize, f64)],
+    pub best_individual: Option<&'s Individual>,
+    pub best_value: Option<f64>,
+    pub evaluations: usize,
+    pub cache_hits: Option<usize>,
+}
+
+/// Logs, records and notifies the progress of a run and decides when it
+/// stops.
+pub(crate) struct Monitor<'a> {
+    config: &'a Config,
+    problem: &'a (dyn Problem + Sync + Send),
+    run: usize,
+    observers: &'a [Box<dyn Observer + Send + Sync>],
+    metrics_sink: Option<&'a Mutex<Box<dyn MetricsSink + Send>>>,
+    checkpoint_sink: Option<&'a (dyn CheckpointSink + Sync)>,
+    interrupt: Option<&'a AtomicBool>,
+    termination: Vec<Box<dyn Termination + Sync + Send>>,
+    started: Instant,
+    /// Time spent before the run was checkpointed.
+    resumed_elapsed: Duration,
+    stagnant_generations: usize,
+    best_generation: usize,
+}
+
+impl<'a> Monitor<'a> {
+    pub fn new(
+        problem: &'a (dyn Problem + Sync + Send),
+        config: &'a Config,
+        run: usize,
+    ) -> Self {
+        Monitor {
+            config,
+            problem,
+            run,
+            observers: &[],
+            metrics_sink: None,
+            checkpoint_sink: None,
+            interrupt: None,
+            termination: termination::from_config(config),
+            started: Instant::now(),
+            resumed_elapsed: Duration::ZERO,
+            stagnant_generations: 0,
+            best_generation: 0,
+        }
+    }
+
+    pub fn set_metrics_sink(
+        &mut self,
+        sink: &'a Mutex<Box<dyn MetricsSink + Send>>,
+    ) {
+        self.metrics_sink = Some(sink);
+    }
+
+    pub fn set_observers(
+        &mut self,
+        observers: &'a [Box<dyn Observer + Send + Sync>],
+    ) {
+        self.observers = observers;
+    }
+
+    pub fn set_checkpoint_sink(
+        &mut self,
+        sink: &'a (dyn CheckpointSink + Sync),
+    ) {
+        self.checkpoint_sink = Some(sink);
+    }
+
+    pub fn set_interrupt(&mut self, interrupt: &'a AtomicBool) {
+        self.interrupt = Some(interrupt);
+    }
+
+    pub fn restore(&mut self, checkpoint: &RunCheckpoint) {
+        self.stagnant_generations = checkpoint.stagnant_generations;
+        self.best_generation = checkpoint.best_generation;
+        self.resumed_elapsed = Duration::from_secs_f64(checkpoint.elapsed_secs);
+    }
+
+    pub fn start(&mut self) {
+        for observer in self.observers {
+            observer.on_run_start(self.run);
+        }
+        self.started = Instant::now();
+    }
+
+    pub fn genocide(&self, generation: usize) {
+        for observer in self.observers {
+            observer.on_genocide(self.run, generation);
+        }
+    }
+
+    /// Reports the generation, returning why the run stops after it if it
+    /// does.
+    pub fn end_generation(
+        &mut self,
+        generation: usize,
+        previous_best: Option<f64>,
+        snapshot: &Snapshot,
+    ) -> Option<StopReason> {
+        if previous_best.is_some() && snapshot.best_value <= previous_best {
+            self.stagnant_generations += 1;
+        } else {
+            self.stagnant_generations = 0;
+            self.best_generation = generation;
+            if let (Some(individual), Some(value)) =
+                (snapshot.best_individual, snapshot.best_value)
+            {
+                for observer in self.observers {
+                    observer
+                        .on_new_best(self.run, generation, individual, value);
+                }
+            }
+        }
+
+        self.log_generation(generation, snapshot);
+        let mut observer_stop = false;
+        for observer in self.observers {
+            observer_stop |= observer
+                .on_generation_end(
+                    self.run,
+                    generation,
+                    snapshot.population,
+                    snapshot.fitness,
+                )
+                .is_break();
+        }
+        let state = RunState {
+            generation,
+            best_value: snapshot.best_value,
+            stagnant_generations: self.stagnant_generations,
+            elapsed: self.elapsed(),
+            evaluations: snapshot.evaluations,
+            population: snapshot.population,
+        };
+        let interrupted = self
+            .interrupt
+            .is_some_and(|interrupt| interrupt.load(Ordering::Relaxed));
+        self.termination
+            .iter()
+            .find_map(|criterion| criterion.check(&state))
+            .or(observer_stop.then_some(StopReason::Observer))
+            .or(interrupted.then_some(StopReason::Interrupted))
+    }
+
+    /// Whether the run is checkpointed after `generation`, which it is
+    /// every `checkpoint_interval` generations until it stops, and when it
+    /// is interrupted.
+    pub fn checkpoint_due(
+        &self,
+        generation: usize,
+        stop_reason: Option<StopReason>,
+    ) -> bool {
+        self.checkpoint_sink.is_some()
+            && match stop_reason {
+                Some(stop_reason) => stop_reason == StopReason::Interrupted,
+                None => {
+                    self.config.checkpoint_interval.is_some_and(|interval| {
+                        interval > 0 && generation.is_multiple_of(interval)
+                    })
+                }
+            }
+    }
+
+    /// # Panics
+    /// If the checkpoint cannot be written
+    pub fn save_checkpoint(
+        &self,
+        generation: usize,
+        populations: Vec<PopulationState>,
+    ) {
+        let Some(sink) = self.checkpoint_sink else {
+            return;
+        };
+        sink.save(RunCheckpoint {
+            run: self.run,
+            generation,
+            stagnant_generations: self.stagnant_generations,
+            best_generation: self.best_generation,
+            elapsed_secs: self.elapsed().as_secs_f64(),
+            populations,
+        })
+        .expect("Failed to write checkpoint");
+    }
+
+    pub fn finish(
+        &self,
+        stop_reason: StopReason,
+        generation: usize,
+        snapshot: &Snapshot,
+    ) -> RunResult {
+        info!("Stop Reason: {}", stop_reason);
+        self.log_run_result(snapshot);
+        let run_result = RunResult {
+            best_individual: snapshot.best_individual.cloned(),
+            best_value: snapshot.best_value,
+            stop_reason,
+            evaluations: snapshot.evaluations,
+            best_generation: self.best_generation,
+            generations: generation,
+            elapsed: self.elapsed(),
+        };
+        for observer in self.observers {
+            observer.on_run_end(self.run, &run_result);
+        }
+        run_result
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.resumed_elapsed + self.started.elapsed()
+    }
+
+    fn log_run_result(&self, snapshot: &Snapshot) {
+        if let Some(best_individual) = snapshot.best_individual {
+            info!("Best Individual: {}", best_individual.clone());
+            info!(
+                "Best Individual Value: {}",
+                snapshot
+                    .best_value
+                    .expect("Unable to retrieve best individual value")
+            );
+            info!(
+                "Best Individual Value Decoded: {}",
+                self.problem
+                    .objective(&self.problem.decode(best_individual))
+            );
+            info!(
+                "Best Individual Decoded: {:?}",
+                self.problem.decode(best_individual)
+            );
+            info!(
+                "Best Individual Constraint: {}",
+                self.problem
+                    .constraint(&self.problem.decode(best_individual))
+            );
+            if let Some(optimum) = self.problem.known_optimum() {
+                let objective = self
+                    .problem
+                    .objective(&self.problem.decode(best_individual));
+                info!(
+                    "Best Individual Optimality Gap: {}",
+                    (objective - optimum).abs() / optimum.abs()
+                );
+            }
+        }
+        info!("Fitness Evaluations: {}", snapshot.evaluations);
+        if let Some(hits) = snapshot.cache_hits {
+            info!("Fitness Cache Hits: {}", hits);
+        }
+    }
+
+    fn log_generation(&self, generation: usize, snapshot: &Snapshot) {
+        let result = snapshot.fitness;
+        let result_mapped = result.iter().map(|(_, value)| value);
+        let best_so_far = snapshot
+            .best_value
+            .expect("Unable to retrieve best individual value");
+        let best = *result_mapped
+            .clone()
+            .max_by(|a, b| a.total_cmp(b))
+            .unwrap_or(&0.0);
+        let mean = result_mapped.clone().sum::<f64>() / result.len() as f64;
+        let worst = *result_mapped
+            .clone()
+            .min_by(|a, b| a.total_cmp(b))
+            .unwrap_or(&0.0);
+        info!(
+            "State Individual: {} {} {} {} {}",
+            generation, best_so_far, best, mean, worst,
+        );
+        if let Some(sink) = self.metrics_sink {
+            let variance = result_mapped
+                .map(|value| (value - mean).powi(2))
+                .sum::<f64>()
+                / result.len() as f64;
+            let metrics = GenerationMetrics {
+                run: self.run,
+                generation,
+                best_so_far,
+                best,
+                mean,
+                worst,
+                std_dev: variance.sqrt(),
+                diversity: snapshot
+                    .population
+                    .diversity(self.config.pop_config.bounds.as_ref()),
+                evaluations: snapshot.evaluations,
+                elapsed_secs: self.elapsed().as_secs_f64(),
+            };
+            sink.lock()
+                .expect("Metrics sink lock poisoned")
+                .record(&metrics)
+                .expect("Failed to record generation metrics");
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use std::ops::ControlFlow;
+
+use population::{Individual, Population};
+
+use crate::RunResult;
+
+/// Hooks called by [`crate::GA`] as a run progresses. Every hook does
+/// nothing by default.
+pub trait Observer {
+    fn on_run_start(&self, _run: usize) {}
+
+    /// Called once the generation is evaluated, with the fitness of each
+    /// individual by index. Returning [`ControlFlow::Break`] stops the run.
+    fn on_generation_end(
+        &self,
+        _run: usize,
+        _generation: usize,
+        _population: &Population,
+        _fitness: &[(usize, f64)],
+    ) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called when the best fitness of the run strictly improves.
+    fn on_new_best(
+        &self,
+        _run: usize,
+        _generation: usize,
+        _individual: &Individual,
+        _value: f64,
+    ) {
+    }
+
+    fn on_genocide(&self, _run: usize, _generation: usize) {}
+
+    fn on_run_end(&self, _run: usize, _result: &RunResult) {}
+}
@@ -0,0 +1,94 @@
+use std::{collections::HashMap, ops::ControlFlow, sync::Mutex};
+
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use population::Population;
+
+use crate::{observer::Observer, RunResult, StopReason};
+
+const TEMPLATE: &str =
+    "[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}";
+
+/// Draws a bar of the completed runs and one bar of generations per run.
+pub struct ProgressReporter {
+    multi_progress_bar: MultiProgress,
+    runs_bar: ProgressBar,
+    run_bars: Mutex<HashMap<usize, ProgressBar>>,
+    qtd_gen: usize,
+}
+
+impl ProgressReporter {
+    /// # Panics
+    /// If the progress bar template is invalid
+    #[must_use]
+    pub fn new(qtd_gen: usize, qtd_runs: usize) -> Self {
+        let multi_progress_bar = MultiProgress::new();
+        let runs_bar =
+            multi_progress_bar.add(ProgressBar::new(qtd_runs as u64));
+        runs_bar.set_style(style());
+        runs_bar.set_message("Runs");
+        ProgressReporter {
+            multi_progress_bar,
+            runs_bar,
+            run_bars: Mutex::new(HashMap::new()),
+            qtd_gen,
+        }
+    }
+}
+
+fn style() -> ProgressStyle {
+    ProgressStyle::with_template(TEMPLATE)
+        .expect("Failed to build progress bar template")
+}
+
+impl Observer for ProgressReporter {
+    fn on_run_start(&self, run: usize) {
+        let bar = self
+            .multi_progress_bar
+            .add(ProgressBar::new(self.qtd_gen as u64));
+        bar.set_style(style());
+        self.run_bars
+            .lock()
+            .expect("Progress bars lock poisoned")
+            .insert(run, bar);
+    }
+
+    fn on_generation_end(
+        &self,
+        run: usize,
+        generation: usize,
+        _: &Population,
+        _: &[(usize, f64)],
+    ) -> ControlFlow<()> {
+        if let Some(bar) = self
+            .run_bars
+            .lock()
+            .expect("Progress bars lock poisoned")
+            .get(&run)
+        {
+            bar.set_position(generation as u64);
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn on_run_end(&self, run: usize, result: &RunResult) {
+        if let Some(bar) = self
+            .run_bars
+            .lock()
+            .expect("Progress bars lock poisoned")
+            .remove(&run)
+        {
+            if result.stop_reason == StopReason::Interrupted {
+                bar.abandon_with_message("Run interrupted");
+                return;
+            }
+            bar.finish_with_message(format!(
+                "Run completed:  {}",
+                result.best_value.unwrap_or(-1.0)
+            ));
+        }
+        self.runs_bar.inc(1);
+        if self.runs_bar.position() >= self.runs_bar.length().unwrap_or(0) {
+            self.runs_bar.finish_with_message("All runs completed");
+        }
+    }
+}
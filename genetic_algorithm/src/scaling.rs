@@ -0,0 +1,245 @@
+use std::collections::VecDeque;
+
+use loader_config::{Config, ScalingMethod};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+const DEFAULT_LINEAR_SCALING_START: f64 = 1.2;
+const DEFAULT_LINEAR_SCALING: f64 = 2.0;
+const DEFAULT_LINEAR_SCALING_RAMP: f64 = 0.8;
+const DEFAULT_SIGMA_SCALING: f64 = 2.0;
+const DEFAULT_POWER_SCALING: f64 = 1.005;
+const DEFAULT_SCALING_WINDOW: usize = 5;
+
+/// Maps the fitness of a generation to the values seen by selection.
+pub trait Scaling {
+    fn scale(
+        &mut self,
+        result: &[(usize, f64)],
+        generation: usize,
+    ) -> Vec<(usize, f64)>;
+
+    /// Values carried from one generation to the next, for checkpoints.
+    fn state(&self) -> Vec<f64> {
+        Vec::new()
+    }
+
+    /// Restores the values returned by [`Scaling::state`].
+    fn restore(&mut self, _state: &[f64]) {}
+}
+
+/// Builds the configured scaling, linear when the config does not set one.
+pub fn from_config(config: &Config) -> Box<dyn Scaling + Sync + Send> {
+    match config.scaling_method.unwrap_or(ScalingMethod::Linear) {
+        ScalingMethod::None => Box::new(NoScaling {}),
+        ScalingMethod::Linear => Box::new(LinearScaling::new(
+            config
+                .linear_scaling_start
+                .unwrap_or(DEFAULT_LINEAR_SCALING_START),
+            config.linear_scaling.unwrap_or(DEFAULT_LINEAR_SCALING),
+            config
+                .linear_scaling_ramp
+                .unwrap_or(DEFAULT_LINEAR_SCALING_RAMP),
+            config.qtd_gen,
+        )),
+        ScalingMethod::Sigma => Box::new(SigmaScaling::new(
+            config.sigma_scaling.unwrap_or(DEFAULT_SIGMA_SCALING),
+        )),
+        ScalingMethod::Power => Box::new(PowerScaling::new(
+            config.power_scaling.unwrap_or(DEFAULT_POWER_SCALING),
+        )),
+        ScalingMethod::Windowing => Box::new(WindowingScaling::new(
+            config.scaling_window.unwrap_or(DEFAULT_SCALING_WINDOW),
+        )),
+    }
+}
+
+pub struct NoScaling {}
+
+impl Scaling for NoScaling {
+    fn scale(
+        &mut self,
+        result: &[(usize, f64)],
+        _: usize,
+    ) -> Vec<(usize, f64)> {
+        result.to_vec()
+    }
+}
+
+/// Linear scaling keeping the average and mapping the best individual to `c`
+/// times it, or the worst to zero when that would turn it negative. `c`
+/// ramps linearly from `start` to `end` over the first `ramp` fraction of
+/// the generations.
+pub struct LinearScaling {
+    start: f64,
+    end: f64,
+    ramp_generations: f64,
+}
+
+impl LinearScaling {
+    pub fn new(start: f64, end: f64, ramp: f64, qtd_gen: usize) -> Self {
+        Self {
+            start,
+            end,
+            ramp_generations: qtd_gen as f64 * ramp,
+        }
+    }
+
+    fn c(&self, generation: usize) -> f64 {
+        let generation = generation as f64;
+        if generation < self.ramp_generations {
+            self.start
+                + (((self.end - self.start) / self.ramp_generations)
+                    * generation)
+        } else {
+            self.end
+        }
+    }
+}
+
+impl Scaling for LinearScaling {
+    fn scale(
+        &mut self,
+        result: &[(usize, f64)],
+        generation: usize,
+    ) -> Vec<(usize, f64)> {
+        let c = self.c(generation);
+        let (min, max, average) = statistics(result);
+        // A converged population has no spread to scale, and scaling it
+        // would divide by `max - average`.
+        if max - min <= f64::EPSILON * max.abs().max(1.0) {
+            return result.to_vec();
+        }
+        let (alpha, beta) = if min > (c * average - max) / (c - 1.0) {
+            (
+                average * (c - 1.0) / (max - average),
+                average * (max - c * average) / (max - average),
+            )
+        } else {
+            (
+                average / (average - min),
+                (-min * average) / (average - min),
+            )
+        };
+        result
+            .par_iter()
+            .map(|(index, value)| (*index, alpha * value + beta))
+            .collect()
+    }
+}
+
+/// Sigma truncation: `f - (average - c * sigma)`, negative values truncated
+/// to zero.
+pub struct SigmaScaling {
+    c: f64,
+}
+
+impl SigmaScaling {
+    pub fn new(c: f64) -> Self {
+        Self { c }
+    }
+}
+
+impl Scaling for SigmaScaling {
+    fn scale(
+        &mut self,
+        result: &[(usize, f64)],
+        _: usize,
+    ) -> Vec<(usize, f64)> {
+        let (_, _, average) = statistics(result);
+        let variance = result
+            .par_iter()
+            .map(|(_, value)| (value - average).powi(2))
+            .sum::<f64>()
+            / result.len() as f64;
+        let offset = average - self.c * variance.sqrt();
+        result
+            .par_iter()
+            .map(|(index, value)| (*index, (value - offset).max(0.0)))
+            .collect()
+    }
+}
+
+/// Power law scaling: `f^k`, negative fitness truncated to zero first.
+pub struct PowerScaling {
+    k: f64,
+}
+
+impl PowerScaling {
+    pub fn new(k: f64) -> Self {
+        Self { k }
+    }
+}
+
+impl Scaling for PowerScaling {
+    fn scale(
+        &mut self,
+        result: &[(usize, f64)],
+        _: usize,
+    ) -> Vec<(usize, f64)> {
+        result
+            .par_iter()
+            .map(|(index, value)| (*index, value.max(0.0).powf(self.k)))
+            .collect()
+    }
+}
+
+/// Windowing: subtracts the worst fitness seen over the last `window`
+/// generations, so selection keeps discriminating once every fitness is
+/// large.
+pub struct WindowingScaling {
+    window: usize,
+    minimums: VecDeque<f64>,
+}
+
+impl WindowingScaling {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(1),
+            minimums: VecDeque::with_capacity(window.max(1)),
+        }
+    }
+}
+
+impl Scaling for WindowingScaling {
+    fn scale(
+        &mut self,
+        result: &[(usize, f64)],
+        _: usize,
+    ) -> Vec<(usize, f64)> {
+        let (min, _, _) = statistics(result);
+        if self.minimums.len() == self.window {
+            self.minimums.pop_front();
+        }
+        self.minimums.push_back(min);
+        let baseline = self.minimums.iter().copied().fold(min, f64::min);
+        result
+            .par_iter()
+            .map(|(index, value)| (*index, value - baseline))
+            .collect()
+    }
+
+    fn state(&self) -> Vec<f64> {
+        self.minimums.iter().copied().collect()
+    }
+
+    fn restore(&mut self, state: &[f64]) {
+        self.minimums = state.iter().copied().collect();
+    }
+}
+
+/// Minimum, maximum and average fitness.
+fn statistics(result: &[(usize, f64)]) -> (f64, f64, f64) {
+    let min = *result
+        .par_iter()
+        .map(|(_, value)| value)
+        .min_by(|a, b| a.total_cmp(b))
+        .expect("Failed to get min value.");
+    let max = *result
+        .par_iter()
+        .map(|(_, value)| value)
+        .max_by(|a, b| a.total_cmp(b))
+        .expect("Failed to get max value.");
+    let average = result.par_iter().map(|(_, value)| value).sum::<f64>()
+        / result.len() as f64;
+    (min, max, average)
+}
@@ -1,9 +1,58 @@
-use rand::Rng;
-use random_choice::random_choice;
-use rayon::iter::{IntoParallelRefIterator, ParallelBridge, ParallelIterator};
+use loader_config::{Config, SelectionMethod};
+use population::RngStream;
+use rand::{
+    distributions::{Distribution, WeightedIndex},
+    seq::SliceRandom,
+    Rng,
+};
+use rayon::iter::{
+    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator,
+    ParallelIterator,
+};
+
+const DEFAULT_TOURNAMENT_SIZE: usize = 3;
+const DEFAULT_RANK_PRESSURE: f64 = 1.5;
+const DEFAULT_RANK_BASE: f64 = 0.95;
+const DEFAULT_TRUNCATION_RATIO: f64 = 0.5;
+const DEFAULT_BOLTZMANN_TEMPERATURE: f64 = 0.1;
 
 pub trait Selection {
-    fn select(&self, result: &[(usize, f64)]) -> Vec<(usize, usize)>;
+    fn select(
+        &self,
+        result: &[(usize, f64)],
+        rng_stream: &RngStream,
+    ) -> Vec<(usize, usize)>;
+}
+
+/// Builds the configured selection method, whose parameters
+/// `Config::validate` checks.
+pub fn from_config(config: &Config) -> Box<dyn Selection + Sync + Send> {
+    match config.selection_method {
+        SelectionMethod::Roulette => Box::new(RouletteWheel::new()),
+        SelectionMethod::Tournament => Box::new(Tournament::new(config.kp)),
+        SelectionMethod::StochasticUniversal => {
+            Box::new(StochasticUniversal::new())
+        }
+        SelectionMethod::LinearRank => Box::new(Rank::new(Ranking::Linear {
+            pressure: config.rank_pressure.unwrap_or(DEFAULT_RANK_PRESSURE),
+        })),
+        SelectionMethod::ExponentialRank => {
+            Box::new(Rank::new(Ranking::Exponential {
+                base: config.rank_base.unwrap_or(DEFAULT_RANK_BASE),
+            }))
+        }
+        SelectionMethod::KTournament => Box::new(KTournament::new(
+            config.tournament_size.unwrap_or(DEFAULT_TOURNAMENT_SIZE),
+        )),
+        SelectionMethod::Truncation => Box::new(Truncation::new(
+            config.truncation_ratio.unwrap_or(DEFAULT_TRUNCATION_RATIO),
+        )),
+        SelectionMethod::Boltzmann => Box::new(Boltzmann::new(
+            config
+                .boltzmann_temperature
+                .unwrap_or(DEFAULT_BOLTZMANN_TEMPERATURE),
+        )),
+    }
 }
 
 pub struct Tournament {
@@ -17,13 +66,17 @@ impl Tournament {
 }
 
 impl Selection for Tournament {
-    fn select(&self, result: &[(usize, f64)]) -> Vec<(usize, usize)> {
+    fn select(
+        &self,
+        result: &[(usize, f64)],
+        rng_stream: &RngStream,
+    ) -> Vec<(usize, usize)> {
         let pop_size = result.len();
         let kp = self.kp;
         let mating_pool: Vec<(usize, usize)> = (0..pop_size / 2)
-            .par_bridge()
-            .map(|_| {
-                let mut rng = rand::thread_rng();
+            .into_par_iter()
+            .map(|i| {
+                let mut rng = rng_stream.fork(i as u64).rng();
                 let parent1 = {
                     let p1 = rng.gen_range(0..pop_size);
                     let p2 = rng.gen_range(0..pop_size);
@@ -68,44 +121,367 @@ impl RouletteWheel {
 }
 
 impl Selection for RouletteWheel {
-    fn select(&self, result: &[(usize, f64)]) -> Vec<(usize, usize)> {
+    fn select(
+        &self,
+        result: &[(usize, f64)],
+        rng_stream: &RngStream,
+    ) -> Vec<(usize, usize)> {
+        let weights: Vec<f64> =
+            result.iter().map(|(_, r)| r.max(0.0)).collect();
+        spin_pairs(result, &weights, rng_stream)
+    }
+}
+
+/// Stochastic universal sampling: a single spin of a roulette with
+/// `pop_size` equally spaced pointers, so every individual is picked within
+/// one of its expected number of copies.
+pub struct StochasticUniversal {}
+
+impl StochasticUniversal {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Selection for StochasticUniversal {
+    fn select(
+        &self,
+        result: &[(usize, f64)],
+        rng_stream: &RngStream,
+    ) -> Vec<(usize, usize)> {
+        let pop_size = result.len();
+        let pointers = pop_size / 2 * 2;
+        let mut rng = rng_stream.rng();
+        let weights: Vec<f64> =
+            result.iter().map(|(_, r)| r.max(0.0)).collect();
+        let total: f64 = weights.iter().sum();
+        let mut selected: Vec<usize> = if total > 0.0 {
+            let spacing = total / pointers as f64;
+            let start = rng.gen::<f64>() * spacing;
+            let mut cumulative = 0.0;
+            let mut individual = 0;
+            (0..pointers)
+                .map(|pointer| {
+                    let position = start + pointer as f64 * spacing;
+                    while individual < pop_size - 1
+                        && cumulative + weights[individual] <= position
+                    {
+                        cumulative += weights[individual];
+                        individual += 1;
+                    }
+                    individual
+                })
+                .collect()
+        } else {
+            (0..pointers).map(|_| rng.gen_range(0..pop_size)).collect()
+        };
+        selected.shuffle(&mut rng);
+        selected
+            .chunks_exact(2)
+            .map(|couple| (result[couple[0]].0, result[couple[1]].0))
+            .collect()
+    }
+}
+
+/// Rank weighting of [`Rank`] selection.
+pub enum Ranking {
+    /// Weights grow linearly from `2 - pressure` for the worst individual to
+    /// `pressure` for the best, `pressure` in `[1, 2]`.
+    Linear { pressure: f64 },
+    /// The individual ranked `i` from the best weighs `base^i`, `base` in
+    /// `(0, 1)`.
+    Exponential { base: f64 },
+}
+
+/// Roulette over the fitness ranks instead of the fitness values, so the
+/// selection pressure does not depend on the fitness scale.
+pub struct Rank {
+    ranking: Ranking,
+}
+
+impl Rank {
+    pub fn new(ranking: Ranking) -> Self {
+        Self { ranking }
+    }
+}
+
+impl Selection for Rank {
+    fn select(
+        &self,
+        result: &[(usize, f64)],
+        rng_stream: &RngStream,
+    ) -> Vec<(usize, usize)> {
+        let pop_size = result.len();
+        let mut ranked: Vec<usize> = (0..pop_size).collect();
+        ranked.sort_by(|&a, &b| {
+            result[a]
+                .1
+                .partial_cmp(&result[b].1)
+                .expect("Failed to compare values.")
+        });
+        let mut weights = vec![0.0; pop_size];
+        for (rank, &individual) in ranked.iter().enumerate() {
+            weights[individual] = match self.ranking {
+                Ranking::Linear { pressure } if pop_size > 1 => {
+                    2.0 - pressure
+                        + 2.0 * (pressure - 1.0) * rank as f64
+                            / (pop_size - 1) as f64
+                }
+                Ranking::Linear { .. } => 1.0,
+                Ranking::Exponential { base } => {
+                    base.powi((pop_size - 1 - rank) as i32)
+                }
+            };
+        }
+        spin_pairs(result, &weights, rng_stream)
+    }
+}
+
+/// Tournament among `size` random individuals, the fittest always wins.
+pub struct KTournament {
+    size: usize,
+}
+
+impl KTournament {
+    /// `size` must be positive.
+    pub fn new(size: usize) -> Self {
+        Self { size }
+    }
+
+    fn tournament<R: Rng + ?Sized>(
+        &self,
+        result: &[(usize, f64)],
+        rng: &mut R,
+    ) -> usize {
+        (0..self.size)
+            .map(|_| rng.gen_range(0..result.len()))
+            .max_by(|&a, &b| {
+                result[a]
+                    .1
+                    .partial_cmp(&result[b].1)
+                    .expect("Failed to compare values.")
+            })
+            .expect("Tournament size must be positive")
+    }
+}
+
+impl Selection for KTournament {
+    fn select(
+        &self,
+        result: &[(usize, f64)],
+        rng_stream: &RngStream,
+    ) -> Vec<(usize, usize)> {
+        (0..result.len() / 2)
+            .into_par_iter()
+            .map(|i| {
+                let mut rng = rng_stream.fork(i as u64).rng();
+                let parent_1 = self.tournament(result, &mut rng);
+                let parent_2 = self.tournament(result, &mut rng);
+                (result[parent_1].0, result[parent_2].0)
+            })
+            .collect()
+    }
+}
+
+/// Parents drawn uniformly among the fittest `ratio` of the population.
+pub struct Truncation {
+    ratio: f64,
+}
+
+impl Truncation {
+    /// `ratio` must lie within `(0, 1]`.
+    pub fn new(ratio: f64) -> Self {
+        Self { ratio }
+    }
+}
+
+impl Selection for Truncation {
+    fn select(
+        &self,
+        result: &[(usize, f64)],
+        rng_stream: &RngStream,
+    ) -> Vec<(usize, usize)> {
         let pop_size = result.len();
-        let result_size = pop_size as f64;
-        let mut rng_choice = random_choice();
-        let general_probabilities = result
-            .par_iter()
-            .map(|(_, r)| (*r) / result_size)
-            .collect::<Vec<f64>>();
-        let pop_index: Vec<usize> =
-            result.par_iter().map(|(i, _)| *i).collect();
-        let parents_1 = rng_choice.random_choice_f64(
-            &pop_index,
-            &general_probabilities,
-            pop_size / 2,
-        );
-        let mating_pool: Vec<(usize, usize)> = parents_1
-            .par_iter()
-            .map(|&parent_1| {
-                let mut rng_choice_clone = random_choice();
-                let probabilities = result
-                    .iter()
-                    .filter(|(i, _)| *i != *parent_1)
-                    .map(|(_, r)| (*r) / result_size)
-                    .collect::<Vec<f64>>();
-                let choices: Vec<usize> = pop_index
-                    .iter()
-                    .filter(|&i| *i != *parent_1)
-                    .copied()
-                    .collect();
-                let parent_2: Vec<usize> = rng_choice_clone
-                    .random_choice_f64(&choices, &probabilities, 1)
-                    .iter()
-                    .map(|&i| *i)
-                    .collect();
-                (*parent_1, *parent_2.first().expect("Parent 2 is empty"))
+        let mut ranked: Vec<usize> = (0..pop_size).collect();
+        ranked.sort_by(|&a, &b| {
+            result[b]
+                .1
+                .partial_cmp(&result[a].1)
+                .expect("Failed to compare values.")
+        });
+        let kept = ((pop_size as f64 * self.ratio).ceil() as usize)
+            .clamp(1, pop_size.max(1));
+        let fittest = &ranked[..kept];
+        (0..pop_size / 2)
+            .into_par_iter()
+            .map(|i| {
+                let mut rng = rng_stream.fork(i as u64).rng();
+                let parent_1 = rng.gen_range(0..kept);
+                let parent_2 = loop {
+                    let parent_2 = rng.gen_range(0..kept);
+                    if parent_2 != parent_1 || kept < 2 {
+                        break parent_2;
+                    }
+                };
+                (result[fittest[parent_1]].0, result[fittest[parent_2]].0)
             })
+            .collect()
+    }
+}
+
+/// Roulette over `exp(fitness / temperature)`: low temperatures favour the
+/// fittest, high temperatures approach a uniform draw.
+pub struct Boltzmann {
+    temperature: f64,
+}
+
+impl Boltzmann {
+    /// `temperature` must be positive.
+    pub fn new(temperature: f64) -> Self {
+        Self { temperature }
+    }
+}
+
+impl Selection for Boltzmann {
+    fn select(
+        &self,
+        result: &[(usize, f64)],
+        rng_stream: &RngStream,
+    ) -> Vec<(usize, usize)> {
+        // Shifted by the best fitness so the exponentials cannot overflow.
+        let best = result
+            .iter()
+            .map(|(_, r)| *r)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let weights: Vec<f64> = result
+            .iter()
+            .map(|(_, r)| ((r - best) / self.temperature).exp())
             .collect();
+        spin_pairs(result, &weights, rng_stream)
+    }
+}
 
-        mating_pool
+/// Pairs spun on a roulette with `weights`, parent 2 on a wheel without
+/// parent 1's slice.
+fn spin_pairs(
+    result: &[(usize, f64)],
+    weights: &[f64],
+    rng_stream: &RngStream,
+) -> Vec<(usize, usize)> {
+    let pop_size = result.len();
+    let wheel = WeightedIndex::new(weights).ok();
+    let mut rng = rng_stream.rng();
+    let parents_1: Vec<usize> = (0..pop_size / 2)
+        .map(|_| spin(wheel.as_ref(), pop_size, &mut rng))
+        .collect();
+    parents_1
+        .par_iter()
+        .enumerate()
+        .map(|(i, &parent_1)| {
+            let mut rng = rng_stream.fork(i as u64).rng();
+            let wheel = wheel.clone().and_then(|mut wheel| {
+                wheel.update_weights(&[(parent_1, &0.0)]).ok()?;
+                Some(wheel)
+            });
+            let parent_2 = loop {
+                let parent_2 = spin(wheel.as_ref(), pop_size, &mut rng);
+                if parent_2 != parent_1 || pop_size < 2 {
+                    break parent_2;
+                }
+            };
+            (result[parent_1].0, result[parent_2].0)
+        })
+        .collect()
+}
+
+/// Spins the roulette, falling back to a uniform draw when every weight is
+/// zero.
+fn spin<R: Rng + ?Sized>(
+    wheel: Option<&WeightedIndex<f64>>,
+    pop_size: usize,
+    rng: &mut R,
+) -> usize {
+    match wheel {
+        Some(wheel) => wheel.sample(rng),
+        None => rng.gen_range(0..pop_size),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fitness by individual, indexes shifted so selections must map them
+    /// back through `result`.
+    fn result(fitness: &[f64]) -> Vec<(usize, f64)> {
+        fitness
+            .iter()
+            .enumerate()
+            .map(|(i, &fitness)| (i + 10, fitness))
+            .collect()
+    }
+
+    fn copies(pairs: &[(usize, usize)], individual: usize) -> usize {
+        pairs
+            .iter()
+            .flat_map(|&(parent_1, parent_2)| [parent_1, parent_2])
+            .filter(|&parent| parent == individual)
+            .count()
+    }
+
+    #[test]
+    fn stochastic_universal_spaces_pointers_evenly() {
+        // Expected copies 3.0, 1.5, 1.5, 2.0, 0.0, 0.0, 0.0, 0.0.
+        let result = result(&[6.0, 3.0, 3.0, 4.0, 0.0, 0.0, 0.0, 0.0]);
+        for seed in 0..50 {
+            let pairs = StochasticUniversal::new()
+                .select(&result, &RngStream::new(seed));
+            assert_eq!(pairs.len(), 4);
+            assert_eq!(copies(&pairs, 10), 3);
+            assert!((1..=2).contains(&copies(&pairs, 11)));
+            assert!((1..=2).contains(&copies(&pairs, 12)));
+            assert_eq!(copies(&pairs, 13), 2);
+            assert!((14..18).all(|individual| copies(&pairs, individual) == 0));
+        }
+    }
+
+    #[test]
+    fn truncation_draws_from_fittest_only() {
+        let fitness = [0.3, 0.9, 0.1, 0.5, 0.8, 0.2, 0.7, 0.0, 0.4, 0.6];
+        let result = result(&fitness);
+        for seed in 0..50 {
+            let pairs =
+                Truncation::new(0.3).select(&result, &RngStream::new(seed));
+            assert_eq!(pairs.len(), 5);
+            for (parent_1, parent_2) in pairs {
+                assert_ne!(parent_1, parent_2);
+                for parent in [parent_1, parent_2] {
+                    assert!([11, 14, 16].contains(&parent));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn k_tournament_pressure_grows_with_size() {
+        let result = result(&[0.1, 0.4, 0.2, 0.3]);
+        let stream = RngStream::new(5);
+        let large = KTournament::new(64).select(&result, &stream);
+        assert_eq!(large.len(), 2);
+        assert!(large.iter().all(|&pair| pair == (11, 11)));
+
+        let fitness = |pairs: &[(usize, usize)]| -> f64 {
+            pairs
+                .iter()
+                .flat_map(|&(parent_1, parent_2)| [parent_1, parent_2])
+                .map(|parent| result[parent - 10].1)
+                .sum::<f64>()
+                / (2 * pairs.len()) as f64
+        };
+        let many = result.repeat(64);
+        let single = KTournament::new(1).select(&many, &stream);
+        let binary = KTournament::new(2).select(&many, &stream);
+        assert!(fitness(&single) < fitness(&binary));
+        assert!(fitness(&binary) < fitness(&large));
     }
 }
@@ -0,0 +1,153 @@
+use std::{fmt::Display, time::Duration};
+
+use loader_config::{BoundConfig, Config};
+use population::Population;
+use serde::{Deserialize, Serialize};
+
+/// Why a run stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StopReason {
+    MaxGenerations,
+    TargetFitness,
+    Stagnation,
+    TimeLimit,
+    MaxEvaluations,
+    LowDiversity,
+    /// An [`crate::observer::Observer`] asked to stop.
+    Observer,
+    /// Interrupted, to be resumed from its checkpoint.
+    Interrupted,
+}
+
+impl Display for StopReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            StopReason::MaxGenerations => "max generations reached",
+            StopReason::TargetFitness => "target fitness reached",
+            StopReason::Stagnation => "best fitness stagnated",
+            StopReason::TimeLimit => "time limit reached",
+            StopReason::MaxEvaluations => "max fitness evaluations reached",
+            StopReason::LowDiversity => "population diversity too low",
+            StopReason::Observer => "stopped by an observer",
+            StopReason::Interrupted => "interrupted",
+        };
+        write!(f, "{reason}")
+    }
+}
+
+/// State of a run after a generation is evaluated.
+pub struct RunState<'a> {
+    pub generation: usize,
+    pub best_value: Option<f64>,
+    /// Generations since the best fitness last strictly improved.
+    pub stagnant_generations: usize,
+    pub elapsed: Duration,
+    pub evaluations: usize,
+    pub population: &'a Population,
+}
+
+pub trait Termination {
+    fn check(&self, state: &RunState) -> Option<StopReason>;
+}
+
+/// Builds the configured stop conditions, the generation limit always
+/// included.
+pub fn from_config(config: &Config) -> Vec<Box<dyn Termination + Sync + Send>> {
+    let mut criteria: Vec<Box<dyn Termination + Sync + Send>> =
+        vec![Box::new(MaxGenerations {
+            generations: config.qtd_gen,
+        })];
+    let Some(termination) = config.termination else {
+        return criteria;
+    };
+    if let Some(target) = termination.target_fitness {
+        criteria.push(Box::new(TargetFitness { target }));
+    }
+    if let Some(generations) = termination.stagnation_generations {
+        criteria.push(Box::new(Stagnation { generations }));
+    }
+    if let Some(seconds) = termination.time_limit_secs {
+        criteria.push(Box::new(TimeLimit {
+            limit: Duration::from_secs_f64(seconds),
+        }));
+    }
+    if let Some(evaluations) = termination.max_evaluations {
+        criteria.push(Box::new(MaxEvaluations { evaluations }));
+    }
+    if let Some(threshold) = termination.min_diversity {
+        criteria.push(Box::new(LowDiversity {
+            threshold,
+            bounds: config.pop_config.bounds,
+        }));
+    }
+    criteria
+}
+
+pub struct MaxGenerations {
+    generations: usize,
+}
+
+impl Termination for MaxGenerations {
+    fn check(&self, state: &RunState) -> Option<StopReason> {
+        (state.generation >= self.generations)
+            .then_some(StopReason::MaxGenerations)
+    }
+}
+
+pub struct TargetFitness {
+    target: f64,
+}
+
+impl Termination for TargetFitness {
+    fn check(&self, state: &RunState) -> Option<StopReason> {
+        state
+            .best_value
+            .filter(|&best| best >= self.target)
+            .map(|_| StopReason::TargetFitness)
+    }
+}
+
+pub struct Stagnation {
+    generations: usize,
+}
+
+impl Termination for Stagnation {
+    fn check(&self, state: &RunState) -> Option<StopReason> {
+        (state.stagnant_generations >= self.generations)
+            .then_some(StopReason::Stagnation)
+    }
+}
+
+pub struct TimeLimit {
+    limit: Duration,
+}
+
+impl Termination for TimeLimit {
+    fn check(&self, state: &RunState) -> Option<StopReason> {
+        (state.elapsed >= self.limit).then_some(StopReason::TimeLimit)
+    }
+}
+
+pub struct MaxEvaluations {
+    evaluations: usize,
+}
+
+impl Termination for MaxEvaluations {
+    fn check(&self, state: &RunState) -> Option<StopReason> {
+        (state.evaluations >= self.evaluations)
+            .then_some(StopReason::MaxEvaluations)
+    }
+}
+
+/// Stops once [`Population::diversity`] falls below `threshold`.
+pub struct LowDiversity {
+    threshold: f64,
+    bounds: Option<BoundConfig>,
+}
+
+impl Termination for LowDiversity {
+    fn check(&self, state: &RunState) -> Option<StopReason> {
+        (state.population.diversity(self.bounds.as_ref()) < self.threshold)
+            .then_some(StopReason::LowDiversity)
+    }
+}
@@ -0,0 +1,145 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard},
+};
+
+use anyhow::Result;
+use genetic_algorithm::checkpoint::{CheckpointSink, RunCheckpoint};
+use loader_config::Config;
+use serde::{Deserialize, Serialize};
+
+use crate::RunRecord;
+
+/// Contents of a checkpoint file: what rebuilds the framework, the
+/// completed runs and the state of the runs in progress.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Problem name, as given to `problem_factory`.
+    pub problem: String,
+    pub instance: String,
+    /// The config, with the seed of the runs set.
+    pub config: Config,
+    pub completed: Vec<RunRecord>,
+    pub in_progress: Vec<RunCheckpoint>,
+}
+
+impl Checkpoint {
+    /// # Errors
+    /// If the file cannot be read or is not a checkpoint
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+}
+
+/// A [`Checkpoint`] rewritten whenever a run is checkpointed or completes.
+pub(crate) struct CheckpointFile {
+    path: PathBuf,
+    state: Mutex<State>,
+}
+
+struct State {
+    checkpoint: Checkpoint,
+    /// Whether the file exists, runs completing before any checkpoint do
+    /// not create it.
+    written: bool,
+}
+
+impl CheckpointFile {
+    pub(crate) fn new(
+        path: PathBuf,
+        checkpoint: Checkpoint,
+        written: bool,
+    ) -> Self {
+        CheckpointFile {
+            path,
+            state: Mutex::new(State {
+                checkpoint,
+                written,
+            }),
+        }
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub(crate) fn set_seed(&self, seed: u64) {
+        self.lock().checkpoint.config.seed = Some(seed);
+    }
+
+    pub(crate) fn completed(&self, run: usize) -> Option<RunRecord> {
+        self.lock()
+            .checkpoint
+            .completed
+            .iter()
+            .find(|record| record.run == run)
+            .cloned()
+    }
+
+    pub(crate) fn in_progress(&self, run: usize) -> Option<RunCheckpoint> {
+        self.lock()
+            .checkpoint
+            .in_progress
+            .iter()
+            .find(|checkpoint| checkpoint.run == run)
+            .cloned()
+    }
+
+    /// Records a completed run in place of its checkpoint.
+    ///
+    /// # Errors
+    /// If the file cannot be written
+    pub(crate) fn complete(&self, record: RunRecord) -> Result<()> {
+        let mut state = self.lock();
+        let checkpoint = &mut state.checkpoint;
+        checkpoint
+            .in_progress
+            .retain(|in_progress| in_progress.run != record.run);
+        checkpoint.completed.push(record);
+        checkpoint.completed.sort_by_key(|record| record.run);
+        if state.written {
+            self.write(&state.checkpoint)?;
+        }
+        Ok(())
+    }
+
+    /// Removes the file, once every run is completed.
+    ///
+    /// # Errors
+    /// If the file cannot be removed
+    pub(crate) fn remove(&self) -> Result<()> {
+        let mut state = self.lock();
+        if state.written {
+            fs::remove_file(&self.path)?;
+            state.written = false;
+        }
+        Ok(())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().expect("Checkpoint lock poisoned")
+    }
+
+    /// Writes a sibling file first, so an interruption while writing leaves
+    /// the previous checkpoint intact.
+    fn write(&self, checkpoint: &Checkpoint) -> Result<()> {
+        let partial = self.path.with_extension("partial");
+        fs::write(&partial, serde_json::to_string(checkpoint)?)?;
+        fs::rename(partial, &self.path)?;
+        Ok(())
+    }
+}
+
+impl CheckpointSink for CheckpointFile {
+    fn save(&self, run_checkpoint: RunCheckpoint) -> Result<()> {
+        let mut state = self.lock();
+        let in_progress = &mut state.checkpoint.in_progress;
+        in_progress.retain(|checkpoint| checkpoint.run != run_checkpoint.run);
+        in_progress.push(run_checkpoint);
+        in_progress.sort_by_key(|checkpoint| checkpoint.run);
+        self.write(&state.checkpoint)?;
+        state.written = true;
+        Ok(())
+    }
+}
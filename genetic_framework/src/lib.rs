@@ -1,70 +1,456 @@
-use genetic_algorithm::GA;
-use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
-use loader_config::Config;
+mod checkpoint;
+mod solution;
+mod summary;
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+pub use checkpoint::Checkpoint;
+use checkpoint::CheckpointFile;
+#[cfg(feature = "progress")]
+pub use genetic_algorithm::progress;
+pub use genetic_algorithm::{
+    metrics, observer, Evaluation, RunResult, StopReason,
+};
+use genetic_algorithm::{Archipelago, GA};
+use loader_config::{Config, Parallelism};
 use log::info;
-use population::Individual;
-use problem_factory::problem::Problem;
+use metrics::MetricsSink;
+use observer::Observer;
+use population::{
+    Crossover, CrossoverRegistry, Mutation, MutationRegistry, RngStream,
+};
+use problem_factory::{error::ProblemError, problem::Problem};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use solution::SolutionWriter;
+pub use summary::{RunRecord, RunSummary, Statistics};
 
 pub struct Framework {
     problem: Box<dyn Problem + Send + Sync>,
     config: Config,
+    crossover_registry: CrossoverRegistry,
+    mutation_registry: MutationRegistry,
+    metrics_sink: Option<Mutex<Box<dyn MetricsSink + Send>>>,
+    observers: Vec<Box<dyn Observer + Send + Sync>>,
+    checkpoint: Option<CheckpointFile>,
+    solutions: Option<SolutionWriter>,
+    interrupt: Arc<AtomicBool>,
 }
 
 impl Framework {
-    #[must_use]
+    /// # Errors
+    /// If the config does not suit the problem
     pub fn new(
         problem: Box<dyn Problem + Send + Sync>,
         config: Config,
+    ) -> Result<Framework, ProblemError> {
+        problem_factory::check_config(&*problem, &config, None)?;
+        Ok(Framework::checked(problem, config))
+    }
+
+    /// Framework for a config already checked against the problem.
+    fn checked(
+        problem: Box<dyn Problem + Send + Sync>,
+        config: Config,
     ) -> Framework {
-        Framework { problem, config }
+        Framework {
+            problem,
+            config,
+            crossover_registry: CrossoverRegistry::default(),
+            mutation_registry: MutationRegistry::default(),
+            metrics_sink: None,
+            observers: Vec::new(),
+            checkpoint: None,
+            solutions: None,
+            interrupt: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Continues the runs of `checkpoint`, checkpointing them into `path`
+    /// again.
+    ///
+    /// # Errors
+    /// If the checkpointed instance cannot be loaded or the checkpointed
+    /// config does not suit it
+    pub fn from_checkpoint<P: Into<PathBuf>>(
+        checkpoint: Checkpoint,
+        path: P,
+    ) -> Result<Framework, ProblemError> {
+        let path = path.into();
+        let problem = problem_factory::build_problem(
+            &checkpoint.problem,
+            &checkpoint.instance,
+        )?;
+        problem_factory::check_config(
+            &*problem,
+            &checkpoint.config,
+            Some(&path),
+        )?;
+        let mut framework =
+            Framework::checked(problem, checkpoint.config.clone());
+        framework.checkpoint =
+            Some(CheckpointFile::new(path, checkpoint, true));
+        Ok(framework)
     }
 
-    /// # Panics
-    /// If I did shit
     #[must_use]
-    pub fn run(&self) -> (Option<Individual>, Option<f64>) {
-        let mut best_individual: Option<Individual> = None;
-        let mut result: Option<f64> = None;
-        let m = MultiProgress::new();
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
 
-        let sty = ProgressStyle::with_template(
-            "[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}",
-        )
-        .expect("Failed to build progress bar template");
+    /// Registers a crossover operator, selected by configs with
+    /// `"crossover_method": {"Custom": "<name>"}`.
+    pub fn register_crossover<F>(&mut self, name: &str, builder: F)
+    where
+        F: Fn(&Config) -> Box<dyn Crossover + Send + Sync>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.crossover_registry.register(name, builder);
+    }
 
-        let pb = m.add(ProgressBar::new(self.config.qtd_runs as u64));
+    /// Registers a mutation operator, selected by configs with
+    /// `"mutation_method": {"Custom": "<name>"}`.
+    pub fn register_mutation<F>(&mut self, name: &str, builder: F)
+    where
+        F: Fn(&Config) -> Box<dyn Mutation + Send + Sync>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.mutation_registry.register(name, builder);
+    }
 
-        pb.set_style(sty);
-        pb.set_message("Runs");
+    /// Records the statistics of every generation of every run into `sink`.
+    pub fn set_metrics_sink(&mut self, sink: Box<dyn MetricsSink + Send>) {
+        self.metrics_sink = Some(Mutex::new(sink));
+    }
+
+    /// Notifies `observer` of the progress of every run.
+    pub fn register_observer<O>(&mut self, observer: O)
+    where
+        O: Observer + Send + Sync + 'static,
+    {
+        self.observers.push(Box::new(observer));
+    }
 
+    /// Checkpoints the runs into `path` every `checkpoint_interval`
+    /// generations and when interrupted, the file being removed once every
+    /// run completes. `problem` and `instance` are those given to
+    /// [`Framework::from_files`], stored to rebuild the problem on resume.
+    pub fn enable_checkpoints<P: Into<PathBuf>>(
+        &mut self,
+        path: P,
+        problem: &str,
+        instance: &str,
+    ) {
+        let checkpoint = Checkpoint {
+            problem: problem.to_string(),
+            instance: instance.to_string(),
+            config: self.config.clone(),
+            completed: Vec::new(),
+            in_progress: Vec::new(),
+        };
+        self.checkpoint =
+            Some(CheckpointFile::new(path.into(), checkpoint, false));
+    }
+
+    /// Writes the best solution of every run to
+    /// `<output>.run-<run>.solution.json`, along with `problem` and
+    /// `instance` as given to [`Framework::from_files`].
+    ///
+    /// # Errors
+    /// If the instance file cannot be read
+    pub fn enable_solutions(
+        &mut self,
+        output: &str,
+        problem: &str,
+        instance: &str,
+    ) -> Result<(), ProblemError> {
+        let instance_hash = problem_factory::instance_hash(instance)?;
+        self.solutions = Some(SolutionWriter::new(
+            output,
+            problem,
+            instance,
+            instance_hash,
+        ));
+        Ok(())
+    }
+
+    /// Path of the solution of `run`, if solutions are written.
+    #[must_use]
+    pub fn solution_path(&self, run: usize) -> Option<String> {
+        self.solutions.as_ref().map(|solutions| solutions.path(run))
+    }
+
+    /// Flag that, once set, stops every run after its current generation,
+    /// checkpointing it, and skips the runs not started yet.
+    #[must_use]
+    pub fn interrupt_handle(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.interrupt)
+    }
+
+    #[must_use]
+    pub fn interrupted(&self) -> bool {
+        self.interrupt.load(Ordering::Relaxed)
+    }
+
+    /// # Errors
+    /// If the config or instance file cannot be loaded, the config does not
+    /// suit the problem or the problem is unknown
+    pub fn from_files<P>(
+        problem: &str,
+        instance: &str,
+        config_path: P,
+    ) -> Result<Framework, ProblemError>
+    where
+        P: AsRef<Path>,
+    {
+        let (problem, config) =
+            problem_factory::problem_factory(problem, instance, config_path)?;
+        Ok(Framework::checked(problem, config))
+    }
+
+    /// Runs `qtd_runs` independent runs, summarizing their results. Only
+    /// the completed runs are summarized when interrupted.
+    ///
+    /// # Panics
+    /// If a fitness is NaN, the configured crossover or mutation is not
+    /// registered, or the metrics, checkpoints or solutions cannot be
+    /// written
+    #[must_use]
+    pub fn run(&self) -> RunSummary {
         info!("Problem: {}", self.problem.get_name());
         info!(
             "Config: {}",
             serde_json::to_string(&self.config)
                 .expect("Failed to convert config to json string")
         );
-        for run in 1..=self.config.qtd_runs {
-            info!("Run: {}", run);
-            let mut ga = GA::new(&*self.problem, &self.config, &m);
-            let (new_individual, new_result) = &ga.run();
-            if result.is_none()
-                || new_result.expect("New result is empty")
-                    > result.expect("Result is empty")
-            {
-                (best_individual, result) = (
-                    Some(
-                        new_individual
-                            .as_ref()
-                            .expect("Unable to retrieve new individual")
-                            .clone(),
-                    ),
-                    *new_result,
-                );
+        let rng_stream = match self.config.seed {
+            Some(seed) => RngStream::new(seed),
+            None => RngStream::from_entropy(),
+        };
+        info!("Seed: {}", rng_stream.seed());
+        if let Some(checkpoint) = &self.checkpoint {
+            checkpoint.set_seed(rng_stream.seed());
+        }
+        let runs: Vec<Option<RunRecord>> =
+            match self.config.parallelism.unwrap_or(Parallelism::Inner) {
+                Parallelism::Inner => (1..=self.config.qtd_runs)
+                    .map(|run| self.run_once(run, &rng_stream))
+                    .collect(),
+                Parallelism::Outer => (1..=self.config.qtd_runs)
+                    .into_par_iter()
+                    .map(|run| self.run_once(run, &rng_stream))
+                    .collect(),
+            };
+        if let Some(sink) = &self.metrics_sink {
+            sink.lock()
+                .expect("Metrics sink lock poisoned")
+                .finish()
+                .expect("Failed to write generation metrics");
+        }
+        if let Some(checkpoint) = &self.checkpoint {
+            if self.interrupted() {
+                info!("Checkpoint: {}", checkpoint.path().display());
+            } else {
+                checkpoint.remove().expect("Failed to remove checkpoint");
             }
-            pb.inc(1);
-            info!("End Run: {}", run);
         }
-        pb.finish_with_message("All runs completed");
-        (best_individual, result)
+        RunSummary::new(
+            runs.into_iter().flatten().collect(),
+            self.config
+                .termination
+                .and_then(|termination| termination.target_fitness),
+        )
+    }
+
+    /// `None` if the run is interrupted or skipped by an interruption.
+    fn run_once(
+        &self,
+        run: usize,
+        rng_stream: &RngStream,
+    ) -> Option<RunRecord> {
+        let checkpoint = self.checkpoint.as_ref();
+        if let Some(record) =
+            checkpoint.and_then(|checkpoint| checkpoint.completed(run))
+        {
+            info!("Run: {} completed before the checkpoint", run);
+            self.write_solution(&record, rng_stream);
+            return Some(record);
+        }
+        if self.interrupted() {
+            return None;
+        }
+        info!("Run: {}", run);
+        let in_progress =
+            checkpoint.and_then(|checkpoint| checkpoint.in_progress(run));
+        let result = if self.config.islands.is_some() {
+            let mut archipelago = Archipelago::new(
+                &*self.problem,
+                &self.config,
+                rng_stream.fork(run as u64),
+                run,
+                &self.crossover_registry,
+                &self.mutation_registry,
+            );
+            archipelago.set_observers(&self.observers);
+            if let Some(sink) = &self.metrics_sink {
+                archipelago.set_metrics_sink(sink);
+            }
+            if let Some(sink) = checkpoint {
+                archipelago.set_checkpoint_sink(sink);
+            }
+            archipelago.set_interrupt(&self.interrupt);
+            if let Some(in_progress) = in_progress {
+                archipelago.restore(in_progress);
+            }
+            archipelago.run()
+        } else {
+            let mut ga = GA::new(
+                &*self.problem,
+                &self.config,
+                rng_stream.fork(run as u64),
+                run,
+                &self.crossover_registry,
+                &self.mutation_registry,
+            );
+            ga.set_observers(&self.observers);
+            if let Some(sink) = &self.metrics_sink {
+                ga.set_metrics_sink(sink);
+            }
+            if let Some(sink) = checkpoint {
+                ga.set_checkpoint_sink(sink);
+            }
+            ga.set_interrupt(&self.interrupt);
+            if let Some(in_progress) = in_progress {
+                ga.restore(in_progress);
+            }
+            ga.run()
+        };
+        if result.stop_reason == StopReason::Interrupted {
+            info!("Interrupted Run: {}", run);
+            return None;
+        }
+        let record = RunRecord::new(run, result, &*self.problem);
+        self.write_solution(&record, rng_stream);
+        if let Some(checkpoint) = checkpoint {
+            checkpoint
+                .complete(record.clone())
+                .expect("Failed to write checkpoint");
+        }
+        info!("End Run: {}", run);
+        Some(record)
+    }
+
+    fn write_solution(&self, record: &RunRecord, rng_stream: &RngStream) {
+        if let Some(solutions) = &self.solutions {
+            let config = Config {
+                seed: Some(rng_stream.seed()),
+                ..self.config.clone()
+            };
+            solutions
+                .write(record, &config, &*self.problem)
+                .expect("Failed to write solution");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::ControlFlow;
+
+    use population::Population;
+
+    use super::*;
+
+    const INSTANCE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../data/instances/sat-3/uf100-01.cnf"
+    );
+
+    fn framework() -> Framework {
+        let config = Config {
+            qtd_gen: 40,
+            qtd_runs: 2,
+            seed: Some(7),
+            ..Config::default()
+        };
+        let problem =
+            problem_factory::build_problem("sat-3", INSTANCE).unwrap();
+        Framework::new(problem, config).unwrap()
+    }
+
+    /// Best fitness and individual of each run.
+    fn bests(summary: &RunSummary) -> Vec<(Option<f64>, Option<String>)> {
+        summary
+            .runs
+            .iter()
+            .map(|record| {
+                (
+                    record.best_value,
+                    record.best_individual.as_ref().map(ToString::to_string),
+                )
+            })
+            .collect()
+    }
+
+    /// Interrupts the run it observes after `generation`.
+    struct Interrupter {
+        interrupt: Arc<AtomicBool>,
+        generation: usize,
+    }
+
+    impl Observer for Interrupter {
+        fn on_generation_end(
+            &self,
+            _run: usize,
+            generation: usize,
+            _population: &Population,
+            _fitness: &[(usize, f64)],
+        ) -> ControlFlow<()> {
+            if generation == self.generation {
+                self.interrupt.store(true, Ordering::Relaxed);
+            }
+            ControlFlow::Continue(())
+        }
+    }
+
+    #[test]
+    fn same_seed_same_best() {
+        let first = framework().run();
+        let second = framework().run();
+        assert_eq!(first.runs.len(), 2);
+        assert_eq!(bests(&first), bests(&second));
+    }
+
+    #[test]
+    fn resumed_run_ends_as_uninterrupted_one() {
+        let uninterrupted = framework().run();
+
+        let path = std::env::temp_dir().join(format!(
+            "genetic_framework-resume-{}.json",
+            std::process::id()
+        ));
+        let mut interrupted = framework();
+        interrupted.enable_checkpoints(&path, "sat-3", INSTANCE);
+        interrupted.register_observer(Interrupter {
+            interrupt: interrupted.interrupt_handle(),
+            generation: 15,
+        });
+        assert!(interrupted.run().runs.is_empty());
+
+        let checkpoint = Checkpoint::load(&path).unwrap();
+        assert_eq!(checkpoint.in_progress.len(), 1);
+        let resumed =
+            Framework::from_checkpoint(checkpoint, &path).unwrap().run();
+        assert!(!path.exists());
+        assert_eq!(bests(&resumed), bests(&uninterrupted));
     }
 }
@@ -0,0 +1,66 @@
+use std::io;
+
+use loader_config::Config;
+use population::Solution;
+use problem_factory::problem::Problem;
+
+use crate::RunRecord;
+
+/// Writes the best solution of every run next to the other outputs.
+pub(crate) struct SolutionWriter {
+    output: String,
+    problem: String,
+    instance: String,
+    instance_hash: String,
+}
+
+impl SolutionWriter {
+    pub(crate) fn new(
+        output: &str,
+        problem: &str,
+        instance: &str,
+        instance_hash: String,
+    ) -> Self {
+        SolutionWriter {
+            output: output.to_string(),
+            problem: problem.to_string(),
+            instance: instance.to_string(),
+            instance_hash,
+        }
+    }
+
+    pub(crate) fn path(&self, run: usize) -> String {
+        format!("{}.run-{}.solution.json", self.output, run)
+    }
+
+    /// Does nothing for a run without a best individual.
+    ///
+    /// # Errors
+    /// If the file cannot be written
+    pub(crate) fn write(
+        &self,
+        record: &RunRecord,
+        config: &Config,
+        problem: &(dyn Problem + Send + Sync),
+    ) -> io::Result<()> {
+        let (Some(chromosome), Some(fitness)) =
+            (&record.best_individual, record.best_value)
+        else {
+            return Ok(());
+        };
+        let decoded = problem.decode(chromosome);
+        Solution {
+            problem: self.problem.clone(),
+            instance: self.instance.clone(),
+            instance_hash: self.instance_hash.clone(),
+            config: config.clone(),
+            run: record.run,
+            chromosome: chromosome.clone(),
+            fitness,
+            objective: problem.objective(&decoded),
+            constraint: problem.constraint(&decoded),
+            decoded,
+        }
+        .save(self.path(record.run))
+    }
+}
@@ -0,0 +1,140 @@
+use genetic_algorithm::{RunResult, StopReason};
+use population::Individual;
+use problem_factory::problem::Problem;
+use serde::{Deserialize, Serialize};
+
+/// Outcome of a single run.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub run: usize,
+    pub best_individual: Option<Individual>,
+    pub best_value: Option<f64>,
+    /// Objective of the decoded best individual.
+    pub objective: Option<f64>,
+    /// Constraint of the decoded best individual.
+    pub constraint: Option<f64>,
+    /// Generation in which the best individual was found.
+    pub best_generation: usize,
+    pub generations: usize,
+    pub evaluations: usize,
+    pub elapsed_secs: f64,
+    pub stop_reason: StopReason,
+}
+
+impl RunRecord {
+    pub(crate) fn new(
+        run: usize,
+        result: RunResult,
+        problem: &(dyn Problem + Send + Sync),
+    ) -> Self {
+        let decoded = result
+            .best_individual
+            .as_ref()
+            .map(|best| problem.decode(best));
+        RunRecord {
+            run,
+            objective: decoded.as_deref().map(|genes| problem.objective(genes)),
+            constraint: decoded
+                .as_deref()
+                .map(|genes| problem.constraint(genes)),
+            best_individual: result.best_individual,
+            best_value: result.best_value,
+            best_generation: result.best_generation,
+            generations: result.generations,
+            evaluations: result.evaluations,
+            elapsed_secs: result.elapsed.as_secs_f64(),
+            stop_reason: result.stop_reason,
+        }
+    }
+}
+
+/// Mean, median, standard deviation, minimum and maximum of a sample.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct Statistics {
+    pub mean: f64,
+    pub median: f64,
+    pub std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Statistics {
+    /// `None` for an empty sample.
+    #[must_use]
+    pub fn from_values(values: &[f64]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let len = sorted.len();
+        let mean = sorted.iter().sum::<f64>() / len as f64;
+        let median = if len.is_multiple_of(2) {
+            (sorted[len / 2 - 1] + sorted[len / 2]) / 2.0
+        } else {
+            sorted[len / 2]
+        };
+        let variance = sorted
+            .iter()
+            .map(|value| (value - mean).powi(2))
+            .sum::<f64>()
+            / len as f64;
+        Some(Statistics {
+            mean,
+            median,
+            std_dev: variance.sqrt(),
+            min: sorted[0],
+            max: sorted[len - 1],
+        })
+    }
+}
+
+/// Every run of a [`crate::Framework`] and their aggregate statistics.
+#[derive(Clone, Debug, Serialize)]
+pub struct RunSummary {
+    pub runs: Vec<RunRecord>,
+    /// Statistics of the best fitness of each run.
+    pub fitness: Option<Statistics>,
+    /// Statistics of the objective of each run's best individual.
+    pub objective: Option<Statistics>,
+    /// Fitness a run must reach to succeed, the configured
+    /// `target_fitness`.
+    pub target: Option<f64>,
+    /// Fraction of the runs reaching `target`.
+    pub success_rate: Option<f64>,
+}
+
+impl RunSummary {
+    pub(crate) fn new(runs: Vec<RunRecord>, target: Option<f64>) -> Self {
+        let fitness: Vec<f64> =
+            runs.iter().filter_map(|run| run.best_value).collect();
+        let objective: Vec<f64> =
+            runs.iter().filter_map(|run| run.objective).collect();
+        let success_rate = target.filter(|_| !runs.is_empty()).map(|target| {
+            fitness.iter().filter(|&&value| value >= target).count() as f64
+                / runs.len() as f64
+        });
+        RunSummary {
+            fitness: Statistics::from_values(&fitness),
+            objective: Statistics::from_values(&objective),
+            runs,
+            target,
+            success_rate,
+        }
+    }
+
+    /// The run with the highest best fitness, the earliest on ties.
+    #[must_use]
+    pub fn best(&self) -> Option<&RunRecord> {
+        self.runs
+            .iter()
+            .filter(|run| run.best_value.is_some())
+            .reduce(|best, run| {
+                if run.best_value > best.best_value {
+                    run
+                } else {
+                    best
+                }
+            })
+    }
+}
@@ -1,6 +1,6 @@
-use std::{fmt::Debug, fs, path::Path};
+use std::{fmt::Debug, fs, path::Path, time::Duration};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -15,15 +15,245 @@ pub enum PopType {
 pub enum SelectionMethod {
     Roulette,
     Tournament,
+    StochasticUniversal,
+    LinearRank,
+    ExponentialRank,
+    KTournament,
+    Truncation,
+    Boltzmann,
 }
 
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum CrossoverMethod {
     OnePoint,
     TwoPoints,
+    /// Cuts at `crossover_points` distinct points, swapping every other
+    /// segment.
+    NPoint,
     Uniform,
     Cycle,
     PartiallyMapped,
+    Order,
+    OrderBased,
+    PositionBased,
+    EdgeRecombination,
+    Arithmetic,
+    BlxAlpha,
+    /// Operator registered by name in a `CrossoverRegistry`.
+    Custom(String),
+}
+
+impl CrossoverMethod {
+    /// Name the method's operator is registered under.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        match self {
+            CrossoverMethod::OnePoint => "OnePoint",
+            CrossoverMethod::TwoPoints => "TwoPoints",
+            CrossoverMethod::NPoint => "NPoint",
+            CrossoverMethod::Uniform => "Uniform",
+            CrossoverMethod::Cycle => "Cycle",
+            CrossoverMethod::PartiallyMapped => "PartiallyMapped",
+            CrossoverMethod::Order => "Order",
+            CrossoverMethod::OrderBased => "OrderBased",
+            CrossoverMethod::PositionBased => "PositionBased",
+            CrossoverMethod::EdgeRecombination => "EdgeRecombination",
+            CrossoverMethod::Arithmetic => "Arithmetic",
+            CrossoverMethod::BlxAlpha => "BlxAlpha",
+            CrossoverMethod::Custom(name) => name,
+        }
+    }
+
+    /// Whether the operator recombines chromosomes of `pop_type`, assumed
+    /// for custom operators.
+    #[must_use]
+    pub fn suits(&self, pop_type: PopType) -> bool {
+        matches!(
+            (self, pop_type),
+            (
+                CrossoverMethod::OnePoint
+                    | CrossoverMethod::TwoPoints
+                    | CrossoverMethod::NPoint
+                    | CrossoverMethod::Uniform,
+                PopType::Binary | PopType::Real | PopType::Integer
+            ) | (
+                CrossoverMethod::Arithmetic | CrossoverMethod::BlxAlpha,
+                PopType::Real
+            ) | (
+                CrossoverMethod::Cycle
+                    | CrossoverMethod::PartiallyMapped
+                    | CrossoverMethod::Order
+                    | CrossoverMethod::OrderBased
+                    | CrossoverMethod::PositionBased
+                    | CrossoverMethod::EdgeRecombination,
+                PopType::Permuted
+            ) | (CrossoverMethod::Custom(_), _)
+        )
+    }
+}
+
+/// Fitness scaling applied before selection.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum ScalingMethod {
+    None,
+    Linear,
+    Sigma,
+    Power,
+    Windowing,
+}
+
+/// How the violation of the constraints weighs against the objective when
+/// selecting parents.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConstraintHandling {
+    /// Objective plus `constraint_penalty` times the violation.
+    StaticPenalty,
+    /// Static penalty growing with the generation `t` by a factor of
+    /// `(c * t)^alpha`.
+    DynamicPenalty,
+    /// Static penalty weighted by a factor multiplied after the best
+    /// individual stays infeasible for a window of generations, and divided
+    /// after it stays feasible.
+    AdaptivePenalty,
+    /// Deb's rules: feasible individuals beat infeasible ones, which are
+    /// compared by violation, and feasible ones by objective. Requires a
+    /// tournament selection.
+    FeasibilityRules,
+    /// Runarsson and Yao's ranking: a stochastic bubble sort comparing
+    /// neighbours by objective when both are feasible or with some
+    /// probability, by violation otherwise.
+    StochasticRanking,
+}
+
+/// Where the cores are spent: `Inner` evaluates and breeds each generation
+/// in parallel, one run after the other, while `Outer` executes the runs
+/// concurrently, each evaluating and breeding on a single thread.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Parallelism {
+    Inner,
+    Outer,
+}
+
+/// Islands each island sends its emigrants to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MigrationTopology {
+    /// The next island, the last one sending to the first.
+    Ring,
+    /// Every other island.
+    FullyConnected,
+    /// Another island drawn at every migration.
+    Random,
+}
+
+/// Individuals copied out of an island when it migrates.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EmigrantPolicy {
+    Best,
+    Random,
+}
+
+/// Individuals of an island replaced by the immigrants.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImmigrantPolicy {
+    Worst,
+    Random,
+}
+
+/// Island model: `islands` populations of `pop_config.pop_size`
+/// individuals evolve in parallel, every `migration_interval` generations
+/// each sending `migrants` individuals along the topology.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct IslandConfig {
+    pub islands: usize,
+    pub topology: MigrationTopology,
+    pub migration_interval: usize,
+    pub migrants: usize,
+    pub emigrant_policy: EmigrantPolicy,
+    pub immigrant_policy: ImmigrantPolicy,
+}
+
+/// Mutation operator. Bit-flip works on binary chromosomes; swap, insertion,
+/// inversion, scramble and displacement on permutations; gaussian,
+/// polynomial and uniform on real chromosomes; creep and random reset on
+/// integers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MutationMethod {
+    BitFlip,
+    Swap,
+    Insertion,
+    Inversion,
+    Scramble,
+    Displacement,
+    Gaussian,
+    Polynomial,
+    Uniform,
+    Creep,
+    RandomReset,
+    /// Operator registered by name in a `MutationRegistry`.
+    Custom(String),
+}
+
+impl MutationMethod {
+    /// Name the method's operator is registered under.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        match self {
+            MutationMethod::BitFlip => "BitFlip",
+            MutationMethod::Swap => "Swap",
+            MutationMethod::Insertion => "Insertion",
+            MutationMethod::Inversion => "Inversion",
+            MutationMethod::Scramble => "Scramble",
+            MutationMethod::Displacement => "Displacement",
+            MutationMethod::Gaussian => "Gaussian",
+            MutationMethod::Polynomial => "Polynomial",
+            MutationMethod::Uniform => "Uniform",
+            MutationMethod::Creep => "Creep",
+            MutationMethod::RandomReset => "RandomReset",
+            MutationMethod::Custom(name) => name,
+        }
+    }
+
+    /// Whether the operator mutates chromosomes of `pop_type`, assumed for
+    /// custom operators.
+    #[must_use]
+    pub fn suits(&self, pop_type: PopType) -> bool {
+        matches!(
+            (self, pop_type),
+            (MutationMethod::BitFlip, PopType::Binary)
+                | (
+                    MutationMethod::Swap
+                        | MutationMethod::Insertion
+                        | MutationMethod::Inversion
+                        | MutationMethod::Scramble
+                        | MutationMethod::Displacement,
+                    PopType::Permuted
+                )
+                | (
+                    MutationMethod::Gaussian
+                        | MutationMethod::Polynomial
+                        | MutationMethod::Uniform,
+                    PopType::Real
+                )
+                | (
+                    MutationMethod::Creep | MutationMethod::RandomReset,
+                    PopType::Integer
+                )
+                | (MutationMethod::Custom(_), _)
+        )
+    }
+}
+
+impl PopType {
+    /// Mutation used when the config does not set `mutation_method`.
+    #[must_use]
+    pub fn default_mutation(&self) -> MutationMethod {
+        match self {
+            PopType::Binary => MutationMethod::BitFlip,
+            PopType::Real => MutationMethod::Gaussian,
+            PopType::Integer => MutationMethod::Creep,
+            PopType::Permuted => MutationMethod::Swap,
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
@@ -31,28 +261,138 @@ pub struct BoundConfig {
     pub upper: f64,
     pub lower: f64,
 }
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+/// How the seeded share of the initial population is built.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum InitStrategy {
+    /// Chromosomes of these solution files, repeated to fill the share.
+    Solutions(Vec<String>),
+    /// The problem's constructive heuristic.
+    Heuristic,
+    /// Latin hypercube sample of the bounds, for real chromosomes.
+    LatinHypercube,
+    /// Random individuals replaced by their opposite within the bounds
+    /// when fitter, for real chromosomes.
+    OppositionBased,
+}
+
+/// Initial population seeded by `strategy` for a `fraction` of it, within
+/// `[0, 1]`, the other individuals random.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InitConfig {
+    pub strategy: InitStrategy,
+    pub fraction: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PopConfig {
     pub dim: usize,
     pub pop_size: usize,
     pub pop_type: PopType,
     pub bounds: Option<BoundConfig>,
+    /// Random individuals only when unset.
+    pub initialization: Option<InitConfig>,
 }
 
-#[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+/// Stop conditions checked after every generation besides `qtd_gen`; a
+/// run stops as soon as any of them holds.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize)]
+pub struct TerminationConfig {
+    /// Best fitness to reach.
+    pub target_fitness: Option<f64>,
+    /// Generations without a strict improvement of the best fitness.
+    pub stagnation_generations: Option<usize>,
+    /// Wall-clock budget of a run, in seconds, at least 0.
+    pub time_limit_secs: Option<f64>,
+    /// Fitness evaluations budget of a run.
+    pub max_evaluations: Option<usize>,
+    /// Population diversity, within `[0, 1]`, to stay above.
+    pub min_diversity: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Config {
     pub pop_config: PopConfig,
     pub qtd_gen: usize,
     pub qtd_runs: usize,
+    /// `Inner` when unset. With `Outer` the log lines of the runs
+    /// interleave, the metrics tell them apart.
+    pub parallelism: Option<Parallelism>,
+    /// A single population when unset.
+    pub islands: Option<IslandConfig>,
+    pub termination: Option<TerminationConfig>,
+    /// Individuals whose fitness is kept in an LRU cache, disabled when
+    /// unset or 0.
+    pub fitness_cache_size: Option<usize>,
+    /// Generations between the checkpoints of a run, which is otherwise
+    /// only checkpointed when interrupted.
+    pub checkpoint_interval: Option<usize>,
     pub generations_to_genocide: usize,
     pub elitism: bool,
     pub selection_method: SelectionMethod,
     pub crossover_method: CrossoverMethod,
     pub crossover_chance: f64,
+    /// Cut points of `NPoint` crossover, 2 when unset.
+    pub crossover_points: Option<usize>,
+    /// Probability of `Uniform` crossover swapping each pair of genes,
+    /// within `[0, 1]`, 0.5 when unset.
+    pub uniform_swap_chance: Option<f64>,
+    /// `alpha` of `BlxAlpha` crossover, at least 0, 0.5 when unset.
+    pub blx_alpha: Option<f64>,
+    pub mutation_method: Option<MutationMethod>,
     pub mutation_chance: f64,
     pub constraint_penalty: f64,
     pub kp: f64,
+    /// Contestants of a `KTournament`, 3 when unset.
+    pub tournament_size: Option<usize>,
+    /// `LinearRank` weight of the best individual, within `[1, 2]`, 1.5
+    /// when unset.
+    pub rank_pressure: Option<f64>,
+    /// `ExponentialRank` weight ratio between consecutive ranks, within
+    /// `(0, 1)`, 0.95 when unset.
+    pub rank_base: Option<f64>,
+    /// Fraction of the population kept by `Truncation`, 0.5 when unset.
+    pub truncation_ratio: Option<f64>,
+    /// `Boltzmann` selection temperature, 0.1 when unset.
+    pub boltzmann_temperature: Option<f64>,
+    /// Fitness scaling, `Linear` when unset.
+    pub scaling_method: Option<ScalingMethod>,
+    /// Final `Linear` scaling ratio between the best and the average
+    /// fitness, 2.0 when unset.
+    pub linear_scaling: Option<f64>,
+    /// Initial `Linear` scaling ratio, 1.2 when unset.
+    pub linear_scaling_start: Option<f64>,
+    /// Fraction of the generations over which the `Linear` ratio ramps from
+    /// its initial to its final value, 0.8 when unset.
+    pub linear_scaling_ramp: Option<f64>,
+    /// Standard deviations below the average truncated by `Sigma` scaling,
+    /// 2.0 when unset.
+    pub sigma_scaling: Option<f64>,
+    /// Exponent of `Power` scaling, 1.005 when unset.
+    pub power_scaling: Option<f64>,
+    /// Generations whose worst fitness `Windowing` scaling subtracts, 5 when
+    /// unset.
+    pub scaling_window: Option<usize>,
+    /// Fitness the parents are selected by, `StaticPenalty` when unset. The
+    /// fitness reported and kept as the best stays the static penalty one.
+    pub constraint_handling: Option<ConstraintHandling>,
+    /// `c` of `DynamicPenalty`, 0.5 when unset.
+    pub dynamic_penalty_c: Option<f64>,
+    /// `alpha` of `DynamicPenalty`, 2.0 when unset.
+    pub dynamic_penalty_alpha: Option<f64>,
+    /// Generations the best individual of `AdaptivePenalty` stays feasible
+    /// or infeasible before the penalty changes, 5 when unset.
+    pub adaptive_penalty_window: Option<usize>,
+    /// Factor `AdaptivePenalty` multiplies or divides the penalty by, 2.0
+    /// when unset.
+    pub adaptive_penalty_factor: Option<f64>,
+    /// Probability of `StochasticRanking` comparing infeasible neighbours
+    /// by objective, 0.45 when unset.
+    pub ranking_probability: Option<f64>,
+    /// Whether individuals are repaired by the problem before being
+    /// evaluated, false when unset.
+    pub repair: Option<bool>,
     pub generation_gap: f64,
+    pub seed: Option<u64>,
 }
 
 impl Default for Config {
@@ -63,18 +403,48 @@ impl Default for Config {
                 pop_size: 10,
                 pop_type: PopType::Binary,
                 bounds: None,
+                initialization: None,
             },
             qtd_gen: 100,
             qtd_runs: 3,
+            parallelism: None,
+            islands: None,
+            termination: None,
+            fitness_cache_size: None,
+            checkpoint_interval: None,
             generations_to_genocide: 250,
             elitism: true,
             selection_method: SelectionMethod::Roulette,
             crossover_method: CrossoverMethod::TwoPoints,
             crossover_chance: 0.9,
+            crossover_points: None,
+            uniform_swap_chance: None,
+            blx_alpha: None,
+            mutation_method: None,
             mutation_chance: 0.03,
             constraint_penalty: -1.0,
             kp: 0.9,
+            tournament_size: None,
+            rank_pressure: None,
+            rank_base: None,
+            truncation_ratio: None,
+            boltzmann_temperature: None,
+            scaling_method: None,
+            linear_scaling: None,
+            linear_scaling_start: None,
+            linear_scaling_ramp: None,
+            sigma_scaling: None,
+            power_scaling: None,
+            scaling_window: None,
+            constraint_handling: None,
+            dynamic_penalty_c: None,
+            dynamic_penalty_alpha: None,
+            adaptive_penalty_window: None,
+            adaptive_penalty_factor: None,
+            ranking_probability: None,
+            repair: None,
             generation_gap: 0.6,
+            seed: None,
         }
     }
 }
@@ -82,20 +452,163 @@ impl Default for Config {
 impl Config {
     /// # Errors
     ///
-    /// Will return `Err` if `filename` does not exist or the user does not have
-    /// permission to read it.
-    ///
-    /// # Panics
-    ///
-    /// Will return `Err` if `filename` does not exist or the user does not have
-    /// permission to read it.
+    /// Will return `Err` if `filename` does not exist, the user does not have
+    /// permission to read it, it is not a valid config file or its settings
+    /// do not fit together.
     pub fn new<P>(path: P) -> Result<Self>
     where
         P: AsRef<Path>,
     {
         let config_str = fs::read_to_string(path)?;
-        let config_json: Value = serde_json::from_str(&config_str).expect("Config file wrong format");
-        let config_data: Config = serde_json::from_value(config_json.get("config").expect("Config file doesnt have config member").to_owned())?;
+        let config_json: Value = serde_json::from_str(&config_str)?;
+        let config_data: Config = serde_json::from_value(
+            config_json
+                .get("config")
+                .ok_or_else(|| {
+                    anyhow!("Config file doesnt have config member")
+                })?
+                .to_owned(),
+        )?;
+        config_data.validate()?;
         Ok(config_data)
     }
+
+    /// Checks the settings that depend on each other.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if a real or integer population has no bounds, or
+    /// its lower bound exceeds the upper one, a sampling initialization is
+    /// set for a population that is not real, a selection parameter is out
+    /// of range, the time limit is negative, the crossover or mutation does
+    /// not suit the population, the uniform crossover swap chance is not a
+    /// probability, the BLX alpha is negative, the island model has no
+    /// islands or more migrants than individuals, or feasibility rules are
+    /// set without a tournament selection.
+    pub fn validate(&self) -> Result<()> {
+        let pop_config = &self.pop_config;
+        if let PopType::Real | PopType::Integer = pop_config.pop_type {
+            let bounds = pop_config.bounds.ok_or_else(|| {
+                anyhow!("{:?} populations require bounds", pop_config.pop_type)
+            })?;
+            if bounds.lower > bounds.upper {
+                return Err(anyhow!(
+                    "Lower bound {} exceeds upper bound {}",
+                    bounds.lower,
+                    bounds.upper
+                ));
+            }
+        }
+        if let Some(InitConfig {
+            strategy:
+                strategy @ (InitStrategy::LatinHypercube
+                | InitStrategy::OppositionBased),
+            ..
+        }) = &pop_config.initialization
+        {
+            if !matches!(pop_config.pop_type, PopType::Real) {
+                return Err(anyhow!(
+                    "{strategy:?} initialization requires a Real population"
+                ));
+            }
+        }
+        self.validate_selection()?;
+        if let Some(seconds) = self
+            .termination
+            .and_then(|termination| termination.time_limit_secs)
+        {
+            if Duration::try_from_secs_f64(seconds).is_err() {
+                return Err(anyhow!(
+                    "time_limit_secs {seconds} is not a duration"
+                ));
+            }
+        }
+        if !self.crossover_method.suits(pop_config.pop_type) {
+            return Err(anyhow!(
+                "{} crossover does not suit {:?} populations",
+                self.crossover_method.name(),
+                pop_config.pop_type
+            ));
+        }
+        if let Some(swap_chance) = self.uniform_swap_chance {
+            if !(0.0..=1.0).contains(&swap_chance) {
+                return Err(anyhow!(
+                    "uniform_swap_chance {swap_chance} is not within [0, 1]"
+                ));
+            }
+        }
+        if let Some(alpha) = self.blx_alpha {
+            if alpha.is_nan() || alpha < 0.0 {
+                return Err(anyhow!("blx_alpha {alpha} is not at least 0"));
+            }
+        }
+        if let Some(mutation_method) = &self.mutation_method {
+            if !mutation_method.suits(pop_config.pop_type) {
+                return Err(anyhow!(
+                    "{} mutation does not suit {:?} populations",
+                    mutation_method.name(),
+                    pop_config.pop_type
+                ));
+            }
+        }
+        if let Some(islands) = &self.islands {
+            if islands.islands == 0 {
+                return Err(anyhow!("The island model requires islands"));
+            }
+            if islands.migrants > pop_config.pop_size {
+                return Err(anyhow!(
+                    "{} migrants exceed the population of {}",
+                    islands.migrants,
+                    pop_config.pop_size
+                ));
+            }
+        }
+        if self.constraint_handling
+            == Some(ConstraintHandling::FeasibilityRules)
+            && !matches!(
+                self.selection_method,
+                SelectionMethod::Tournament | SelectionMethod::KTournament
+            )
+        {
+            return Err(anyhow!(
+                "FeasibilityRules constraint handling requires a Tournament \
+                 or KTournament selection, not {:?}",
+                self.selection_method
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks the parameters of the selection methods that are set.
+    fn validate_selection(&self) -> Result<()> {
+        let out_of_range = |name: &str, value: f64, range: &str| {
+            Err(anyhow!("{name} {value} is not within {range}"))
+        };
+        if let Some(pressure) = self.rank_pressure {
+            if !(1.0..=2.0).contains(&pressure) {
+                return out_of_range("rank_pressure", pressure, "[1, 2]");
+            }
+        }
+        if let Some(base) = self.rank_base {
+            if !(base > 0.0 && base < 1.0) {
+                return out_of_range("rank_base", base, "(0, 1)");
+            }
+        }
+        if self.tournament_size == Some(0) {
+            return Err(anyhow!("tournament_size must be positive"));
+        }
+        if let Some(ratio) = self.truncation_ratio {
+            if !(ratio > 0.0 && ratio <= 1.0) {
+                return out_of_range("truncation_ratio", ratio, "(0, 1]");
+            }
+        }
+        if let Some(temperature) = self.boltzmann_temperature {
+            if !(temperature > 0.0 && temperature.is_finite()) {
+                return Err(anyhow!(
+                    "boltzmann_temperature {temperature} is not positive"
+                ));
+            }
+        }
+        Ok(())
+    }
 }
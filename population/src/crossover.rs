@@ -1,59 +1,182 @@
 use std::collections::{HashMap, HashSet};
 
-use async_trait::async_trait;
-use rand::Rng;
+use loader_config::{Config, CrossoverMethod};
+use rand::{
+    seq::{index, SliceRandom},
+    Rng, RngCore,
+};
 
 use crate::Individual;
 
-#[async_trait]
-pub trait Crossover
-where
-    Self: Sized,
-{
+const DEFAULT_CROSSOVER_POINTS: usize = 2;
+const DEFAULT_UNIFORM_SWAP_CHANCE: f64 = 0.5;
+const DEFAULT_BLX_ALPHA: f64 = 0.5;
+
+pub trait Crossover {
     fn crossover(
+        &self,
         parent_1: &Individual,
         parent_2: &Individual,
+        rng: &mut dyn RngCore,
     ) -> (Individual, Individual);
 }
 
+/// Builds a crossover operator from the run config.
+pub type CrossoverBuilder =
+    Box<dyn Fn(&Config) -> Box<dyn Crossover + Send + Sync> + Send + Sync>;
+
+/// Crossover operators by [`CrossoverMethod::name`]. The default registry
+/// holds every built-in method; downstream crates register their own
+/// operators and select them with `CrossoverMethod::Custom`.
+pub struct CrossoverRegistry {
+    builders: HashMap<String, CrossoverBuilder>,
+}
+
+impl CrossoverRegistry {
+    /// Registry without any operator.
+    #[must_use]
+    pub fn empty() -> Self {
+        CrossoverRegistry {
+            builders: HashMap::new(),
+        }
+    }
+
+    /// Registers `builder` under `name`, replacing any operator already
+    /// registered with it.
+    pub fn register<F>(&mut self, name: &str, builder: F)
+    where
+        F: Fn(&Config) -> Box<dyn Crossover + Send + Sync>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.builders.insert(name.to_string(), Box::new(builder));
+    }
+
+    /// Builds the operator selected by `config.crossover_method`, `None` if
+    /// no operator is registered under its name.
+    #[must_use]
+    pub fn build(
+        &self,
+        config: &Config,
+    ) -> Option<Box<dyn Crossover + Send + Sync>> {
+        self.builders
+            .get(config.crossover_method.name())
+            .map(|builder| builder(config))
+    }
+}
+
+impl Default for CrossoverRegistry {
+    fn default() -> Self {
+        let mut registry = CrossoverRegistry::empty();
+        let builtins: [(CrossoverMethod, CrossoverBuilder); 12] = [
+            (
+                CrossoverMethod::OnePoint,
+                Box::new(|_| Box::new(OnePointCrossover {})),
+            ),
+            (
+                CrossoverMethod::TwoPoints,
+                Box::new(|_| Box::new(TwoPointsCrossover {})),
+            ),
+            (
+                CrossoverMethod::NPoint,
+                Box::new(|config| {
+                    Box::new(NPointCrossover::new(
+                        config
+                            .crossover_points
+                            .unwrap_or(DEFAULT_CROSSOVER_POINTS),
+                    ))
+                }),
+            ),
+            (
+                CrossoverMethod::Uniform,
+                Box::new(|config| {
+                    Box::new(UniformCrossover::new(
+                        config
+                            .uniform_swap_chance
+                            .unwrap_or(DEFAULT_UNIFORM_SWAP_CHANCE),
+                    ))
+                }),
+            ),
+            (
+                CrossoverMethod::Cycle,
+                Box::new(|_| Box::new(CycleCrossover {})),
+            ),
+            (
+                CrossoverMethod::PartiallyMapped,
+                Box::new(|_| Box::new(PartiallyMappedCrossover {})),
+            ),
+            (
+                CrossoverMethod::Order,
+                Box::new(|_| Box::new(OrderCrossover {})),
+            ),
+            (
+                CrossoverMethod::OrderBased,
+                Box::new(|_| Box::new(OrderBasedCrossover {})),
+            ),
+            (
+                CrossoverMethod::PositionBased,
+                Box::new(|_| Box::new(PositionBasedCrossover {})),
+            ),
+            (
+                CrossoverMethod::EdgeRecombination,
+                Box::new(|_| Box::new(EdgeRecombinationCrossover {})),
+            ),
+            (
+                CrossoverMethod::Arithmetic,
+                Box::new(|_| Box::new(ArithmeticCrossover {})),
+            ),
+            (
+                CrossoverMethod::BlxAlpha,
+                Box::new(|config| {
+                    Box::new(BlxAlphaCrossover::new(
+                        config.blx_alpha.unwrap_or(DEFAULT_BLX_ALPHA),
+                    ))
+                }),
+            ),
+        ];
+        for (method, builder) in builtins {
+            registry.builders.insert(method.name().to_string(), builder);
+        }
+        registry
+    }
+}
+
 pub struct OnePointCrossover {}
 
 impl Crossover for OnePointCrossover {
     fn crossover(
+        &self,
         parent_1: &Individual,
         parent_2: &Individual,
+        rng: &mut dyn RngCore,
     ) -> (Individual, Individual) {
         match (parent_1, parent_2) {
             (Individual::Binary(genes_1), Individual::Binary(genes_2)) => {
-                let mut rng = rand::thread_rng();
-                let crossover_point = rng.gen_range(0..genes_1.len());
-                let genes_iter = genes_1
-                    .iter()
-                    .take(crossover_point)
-                    .zip(genes_2.iter().take(crossover_point));
-                let genes_iter_end = genes_1
-                    .iter()
-                    .skip(crossover_point)
-                    .zip(genes_2.iter().skip(crossover_point));
-                let (child_genes_1, child_genes_2) = genes_iter
-                    .chain(
-                        genes_iter_end.map(|(gene_1, gene_2)| (gene_2, gene_1)),
-                    )
-                    .unzip();
+                let (child_genes_1, child_genes_2) =
+                    one_point(genes_1, genes_2, rng);
                 (
                     Individual::Binary(child_genes_1),
                     Individual::Binary(child_genes_2),
                 )
             }
-            (Individual::Binary(_), Individual::Permuted(_)) => {
-                todo!()
-            }
-            (Individual::Permuted(_), Individual::Binary(_)) => {
-                todo!()
+            (Individual::Real(genes_1), Individual::Real(genes_2)) => {
+                let (child_genes_1, child_genes_2) =
+                    one_point(genes_1, genes_2, rng);
+                (
+                    Individual::Real(child_genes_1),
+                    Individual::Real(child_genes_2),
+                )
             }
-            (Individual::Permuted(_), Individual::Permuted(_)) => {
-                todo!()
+            (Individual::Integer(genes_1), Individual::Integer(genes_2)) => {
+                let (child_genes_1, child_genes_2) =
+                    one_point(genes_1, genes_2, rng);
+                (
+                    Individual::Integer(child_genes_1),
+                    Individual::Integer(child_genes_2),
+                )
             }
+            _ => unsuited(parent_1),
         }
     }
 }
@@ -62,89 +185,214 @@ pub struct TwoPointsCrossover {}
 
 impl Crossover for TwoPointsCrossover {
     fn crossover(
+        &self,
         parent_1: &Individual,
         parent_2: &Individual,
+        rng: &mut dyn RngCore,
     ) -> (Individual, Individual) {
         match (parent_1, parent_2) {
             (Individual::Binary(genes_1), Individual::Binary(genes_2)) => {
-                let mut rng = rand::thread_rng();
-                let crossover_point_1 = rng.gen_range(0..genes_1.len());
-                if crossover_point_1 >= genes_1.len() - 1 {
-                    return (parent_1.clone(), parent_2.clone());
-                }
-                let crossover_point_2 =
-                    rng.gen_range((crossover_point_1 + 1)..genes_1.len());
-                let genes_iter_start = genes_1
-                    .iter()
-                    .take(crossover_point_1)
-                    .zip(genes_2.iter().take(crossover_point_1));
-                let genes_iter_middle = genes_1
-                    .iter()
-                    .skip(crossover_point_1)
-                    .take(crossover_point_2 - crossover_point_1)
-                    .zip(
-                        genes_2
-                            .iter()
-                            .skip(crossover_point_1)
-                            .take(crossover_point_2 - crossover_point_1),
-                    );
-                let genes_iter_end = genes_1
-                    .iter()
-                    .skip(crossover_point_2)
-                    .zip(genes_2.iter().skip(crossover_point_2));
-
-                let (child_genes_1, child_genes_2) = genes_iter_start
-                    .chain(
-                        genes_iter_middle
-                            .map(|(gene_1, gene_2)| (gene_2, gene_1)),
-                    )
-                    .chain(genes_iter_end)
-                    .unzip();
+                let (child_genes_1, child_genes_2) =
+                    two_points(genes_1, genes_2, rng);
                 (
                     Individual::Binary(child_genes_1),
                     Individual::Binary(child_genes_2),
                 )
             }
-            (Individual::Binary(_), Individual::Permuted(_)) => {
-                todo!()
+            (Individual::Real(genes_1), Individual::Real(genes_2)) => {
+                let (child_genes_1, child_genes_2) =
+                    two_points(genes_1, genes_2, rng);
+                (
+                    Individual::Real(child_genes_1),
+                    Individual::Real(child_genes_2),
+                )
+            }
+            (Individual::Integer(genes_1), Individual::Integer(genes_2)) => {
+                let (child_genes_1, child_genes_2) =
+                    two_points(genes_1, genes_2, rng);
+                (
+                    Individual::Integer(child_genes_1),
+                    Individual::Integer(child_genes_2),
+                )
+            }
+            _ => unsuited(parent_1),
+        }
+    }
+}
+
+/// Cuts the parents at `points` distinct points, at most one between each
+/// pair of genes, and swaps every other segment from the first cut on.
+pub struct NPointCrossover {
+    points: usize,
+}
+
+impl NPointCrossover {
+    #[must_use]
+    pub fn new(points: usize) -> Self {
+        NPointCrossover { points }
+    }
+}
+
+impl Crossover for NPointCrossover {
+    fn crossover(
+        &self,
+        parent_1: &Individual,
+        parent_2: &Individual,
+        rng: &mut dyn RngCore,
+    ) -> (Individual, Individual) {
+        match (parent_1, parent_2) {
+            (Individual::Binary(genes_1), Individual::Binary(genes_2)) => {
+                let (child_genes_1, child_genes_2) =
+                    n_points(genes_1, genes_2, self.points, rng);
+                (
+                    Individual::Binary(child_genes_1),
+                    Individual::Binary(child_genes_2),
+                )
             }
-            (Individual::Permuted(_), Individual::Binary(_)) => {
-                todo!()
+            (Individual::Real(genes_1), Individual::Real(genes_2)) => {
+                let (child_genes_1, child_genes_2) =
+                    n_points(genes_1, genes_2, self.points, rng);
+                (
+                    Individual::Real(child_genes_1),
+                    Individual::Real(child_genes_2),
+                )
             }
-            (Individual::Permuted(_), Individual::Permuted(_)) => {
-                todo!()
+            (Individual::Integer(genes_1), Individual::Integer(genes_2)) => {
+                let (child_genes_1, child_genes_2) =
+                    n_points(genes_1, genes_2, self.points, rng);
+                (
+                    Individual::Integer(child_genes_1),
+                    Individual::Integer(child_genes_2),
+                )
             }
+            _ => unsuited(parent_1),
         }
     }
 }
 
-pub struct UniformCrossover {}
+/// Swaps each pair of genes with probability `swap_chance`.
+pub struct UniformCrossover {
+    swap_chance: f64,
+}
+
+impl UniformCrossover {
+    #[must_use]
+    pub fn new(swap_chance: f64) -> Self {
+        UniformCrossover { swap_chance }
+    }
+}
+
 impl Crossover for UniformCrossover {
     fn crossover(
+        &self,
         parent_1: &Individual,
         parent_2: &Individual,
+        rng: &mut dyn RngCore,
     ) -> (Individual, Individual) {
         match (parent_1, parent_2) {
             (Individual::Binary(genes_1), Individual::Binary(genes_2)) => {
-                let mut rng = rand::thread_rng();
-                let genes_iter = genes_1.iter().zip(genes_2);
-                let (child_genes_1, child_genes_2) = genes_iter
+                let (child_genes_1, child_genes_2) =
+                    uniform(genes_1, genes_2, self.swap_chance, rng);
+                (
+                    Individual::Binary(child_genes_1),
+                    Individual::Binary(child_genes_2),
+                )
+            }
+            (Individual::Real(genes_1), Individual::Real(genes_2)) => {
+                let (child_genes_1, child_genes_2) =
+                    uniform(genes_1, genes_2, self.swap_chance, rng);
+                (
+                    Individual::Real(child_genes_1),
+                    Individual::Real(child_genes_2),
+                )
+            }
+            (Individual::Integer(genes_1), Individual::Integer(genes_2)) => {
+                let (child_genes_1, child_genes_2) =
+                    uniform(genes_1, genes_2, self.swap_chance, rng);
+                (
+                    Individual::Integer(child_genes_1),
+                    Individual::Integer(child_genes_2),
+                )
+            }
+            _ => unsuited(parent_1),
+        }
+    }
+}
+
+/// Whole arithmetic recombination: both children are convex combinations of
+/// the parents, weighted by a single random factor.
+pub struct ArithmeticCrossover {}
+
+impl Crossover for ArithmeticCrossover {
+    fn crossover(
+        &self,
+        parent_1: &Individual,
+        parent_2: &Individual,
+        rng: &mut dyn RngCore,
+    ) -> (Individual, Individual) {
+        match (parent_1, parent_2) {
+            (Individual::Real(genes_1), Individual::Real(genes_2)) => {
+                let alpha = rng.gen::<f64>();
+                let (child_genes_1, child_genes_2) = genes_1
+                    .iter()
+                    .zip(genes_2)
                     .map(|(&gene_1, &gene_2)| {
-                        if rng.gen_bool(0.5) {
-                            (gene_1, gene_2)
-                        } else {
-                            (gene_2, gene_1)
-                        }
+                        (
+                            alpha * gene_1 + (1.0 - alpha) * gene_2,
+                            (1.0 - alpha) * gene_1 + alpha * gene_2,
+                        )
                     })
                     .unzip();
                 (
-                    Individual::Binary(child_genes_1),
-                    Individual::Binary(child_genes_2),
+                    Individual::Real(child_genes_1),
+                    Individual::Real(child_genes_2),
                 )
             }
-            (Individual::Binary(_), Individual::Permuted(_)) => todo!(),
-            (Individual::Permuted(_), Individual::Binary(_)) => todo!(),
-            (Individual::Permuted(_), Individual::Permuted(_)) => todo!(),
+            _ => unsuited(parent_1),
+        }
+    }
+}
+
+/// Blend crossover (BLX-α): each child gene is drawn uniformly from the
+/// parents' interval extended by `alpha` times its length on both sides.
+/// Children may leave the bounds; mutation clamps them back.
+pub struct BlxAlphaCrossover {
+    alpha: f64,
+}
+
+impl BlxAlphaCrossover {
+    #[must_use]
+    pub fn new(alpha: f64) -> Self {
+        BlxAlphaCrossover { alpha }
+    }
+}
+
+impl Crossover for BlxAlphaCrossover {
+    fn crossover(
+        &self,
+        parent_1: &Individual,
+        parent_2: &Individual,
+        rng: &mut dyn RngCore,
+    ) -> (Individual, Individual) {
+        match (parent_1, parent_2) {
+            (Individual::Real(genes_1), Individual::Real(genes_2)) => {
+                let (child_genes_1, child_genes_2) = genes_1
+                    .iter()
+                    .zip(genes_2)
+                    .map(|(&gene_1, &gene_2)| {
+                        let lower = gene_1.min(gene_2);
+                        let upper = gene_1.max(gene_2);
+                        let extension = self.alpha * (upper - lower);
+                        let range = (lower - extension)..=(upper + extension);
+                        (rng.gen_range(range.clone()), rng.gen_range(range))
+                    })
+                    .unzip();
+                (
+                    Individual::Real(child_genes_1),
+                    Individual::Real(child_genes_2),
+                )
+            }
+            _ => unsuited(parent_1),
         }
     }
 }
@@ -153,19 +401,12 @@ pub struct CycleCrossover {}
 
 impl Crossover for CycleCrossover {
     fn crossover(
+        &self,
         parent_1: &Individual,
         parent_2: &Individual,
+        _rng: &mut dyn RngCore,
     ) -> (Individual, Individual) {
         match (parent_1, parent_2) {
-            (Individual::Binary(_), Individual::Binary(_)) => {
-                todo!()
-            }
-            (Individual::Binary(_), Individual::Permuted(_)) => {
-                todo!()
-            }
-            (Individual::Permuted(_), Individual::Binary(_)) => {
-                todo!()
-            }
             (Individual::Permuted(genes_1), Individual::Permuted(genes_2)) => {
                 let mut visited: HashSet<usize> =
                     HashSet::with_capacity(genes_1.len());
@@ -201,6 +442,7 @@ impl Crossover for CycleCrossover {
                     Individual::Permuted(child_genes_2),
                 )
             }
+            _ => unsuited(parent_1),
         }
     }
 }
@@ -208,21 +450,13 @@ impl Crossover for CycleCrossover {
 pub struct PartiallyMappedCrossover {}
 impl Crossover for PartiallyMappedCrossover {
     fn crossover(
+        &self,
         parent_1: &Individual,
         parent_2: &Individual,
+        rng: &mut dyn RngCore,
     ) -> (Individual, Individual) {
         match (parent_1, parent_2) {
-            (Individual::Binary(_), Individual::Binary(_)) => {
-                todo!()
-            }
-            (Individual::Binary(_), Individual::Permuted(_)) => {
-                todo!()
-            }
-            (Individual::Permuted(_), Individual::Binary(_)) => {
-                todo!()
-            }
             (Individual::Permuted(genes_1), Individual::Permuted(genes_2)) => {
-                let mut rng = rand::thread_rng();
                 let crossover_point_1 = rng.gen_range(0..genes_1.len());
                 if crossover_point_1 >= genes_1.len() - 1 {
                     return (parent_1.clone(), parent_2.clone());
@@ -313,6 +547,500 @@ impl Crossover for PartiallyMappedCrossover {
                     Individual::Permuted(child_genes_2),
                 )
             }
+            _ => unsuited(parent_1),
+        }
+    }
+}
+
+/// Order crossover (OX1): each child keeps a random segment of one parent
+/// and fills the remaining positions, starting after the segment, with the
+/// other parent's genes in the order they appear from that point.
+pub struct OrderCrossover {}
+
+impl Crossover for OrderCrossover {
+    fn crossover(
+        &self,
+        parent_1: &Individual,
+        parent_2: &Individual,
+        rng: &mut dyn RngCore,
+    ) -> (Individual, Individual) {
+        match (parent_1, parent_2) {
+            (Individual::Permuted(genes_1), Individual::Permuted(genes_2)) => {
+                let crossover_point_1 = rng.gen_range(0..genes_1.len());
+                if crossover_point_1 >= genes_1.len() - 1 {
+                    return (parent_1.clone(), parent_2.clone());
+                }
+                let crossover_point_2 =
+                    rng.gen_range((crossover_point_1 + 1)..genes_1.len());
+                let segment = crossover_point_1..crossover_point_2;
+                (
+                    Individual::Permuted(order_fill(
+                        genes_1,
+                        genes_2,
+                        segment.clone(),
+                    )),
+                    Individual::Permuted(order_fill(genes_2, genes_1, segment)),
+                )
+            }
+            _ => unsuited(parent_1),
+        }
+    }
+}
+
+/// Order-based crossover (OX2): the genes found at random positions of one
+/// parent are rearranged in the other parent to follow the first one's
+/// order, every other gene keeps its place.
+pub struct OrderBasedCrossover {}
+
+impl Crossover for OrderBasedCrossover {
+    fn crossover(
+        &self,
+        parent_1: &Individual,
+        parent_2: &Individual,
+        rng: &mut dyn RngCore,
+    ) -> (Individual, Individual) {
+        match (parent_1, parent_2) {
+            (Individual::Permuted(genes_1), Individual::Permuted(genes_2)) => {
+                let selected = random_positions(genes_1.len(), rng);
+                (
+                    Individual::Permuted(order_based(
+                        genes_1, genes_2, &selected,
+                    )),
+                    Individual::Permuted(order_based(
+                        genes_2, genes_1, &selected,
+                    )),
+                )
+            }
+            _ => unsuited(parent_1),
+        }
+    }
+}
+
+/// Position-based crossover (POS): each child keeps the genes at random
+/// positions of one parent and fills the other positions with the remaining
+/// genes in the other parent's order.
+pub struct PositionBasedCrossover {}
+
+impl Crossover for PositionBasedCrossover {
+    fn crossover(
+        &self,
+        parent_1: &Individual,
+        parent_2: &Individual,
+        rng: &mut dyn RngCore,
+    ) -> (Individual, Individual) {
+        match (parent_1, parent_2) {
+            (Individual::Permuted(genes_1), Individual::Permuted(genes_2)) => {
+                let selected = random_positions(genes_1.len(), rng);
+                (
+                    Individual::Permuted(position_based(
+                        genes_1, genes_2, &selected,
+                    )),
+                    Individual::Permuted(position_based(
+                        genes_2, genes_1, &selected,
+                    )),
+                )
+            }
+            _ => unsuited(parent_1),
         }
     }
 }
+
+/// Edge recombination crossover (ERX): children are built from the union of
+/// the parents' adjacencies, always moving to the neighbour with the fewest
+/// unused edges, so most of the tour edges are inherited. Each child starts
+/// from its parent's first gene.
+pub struct EdgeRecombinationCrossover {}
+
+impl Crossover for EdgeRecombinationCrossover {
+    fn crossover(
+        &self,
+        parent_1: &Individual,
+        parent_2: &Individual,
+        rng: &mut dyn RngCore,
+    ) -> (Individual, Individual) {
+        match (parent_1, parent_2) {
+            (Individual::Permuted(genes_1), Individual::Permuted(genes_2)) => {
+                let position: HashMap<usize, usize> = genes_1
+                    .iter()
+                    .enumerate()
+                    .map(|(index, &gene)| (gene, index))
+                    .collect();
+                let mut edges: Vec<Vec<usize>> =
+                    vec![Vec::new(); genes_1.len()];
+                for genes in [genes_1, genes_2] {
+                    let indexes: Vec<usize> =
+                        genes.iter().map(|gene| position[gene]).collect();
+                    let next = indexes.iter().cycle().skip(1);
+                    for (&from, &to) in indexes.iter().zip(next) {
+                        if from == to || edges[from].contains(&to) {
+                            continue;
+                        }
+                        edges[from].push(to);
+                        edges[to].push(from);
+                    }
+                }
+                let child_1 = edge_recombination(
+                    genes_1,
+                    edges.clone(),
+                    position[&genes_1[0]],
+                    rng,
+                );
+                let child_2 = edge_recombination(
+                    genes_1,
+                    edges,
+                    position[&genes_2[0]],
+                    rng,
+                );
+                (Individual::Permuted(child_1), Individual::Permuted(child_2))
+            }
+            _ => unsuited(parent_1),
+        }
+    }
+}
+
+/// Child with `donor`'s genes inside `segment`, the other positions filled
+/// cyclically from the segment end with `filler`'s missing genes.
+fn order_fill(
+    donor: &[usize],
+    filler: &[usize],
+    segment: std::ops::Range<usize>,
+) -> Vec<usize> {
+    let len = donor.len();
+    let kept: HashSet<usize> = donor[segment.clone()].iter().copied().collect();
+    let mut child = donor.to_vec();
+    let mut missing = (0..len)
+        .map(|offset| filler[(segment.end + offset) % len])
+        .filter(|gene| !kept.contains(gene));
+    for offset in 0..(len - segment.len()) {
+        let index = (segment.end + offset) % len;
+        child[index] = missing.next().expect("filler to be a permutation");
+    }
+    child
+}
+
+/// Each position is selected with probability one half.
+fn random_positions<R: Rng + ?Sized>(len: usize, rng: &mut R) -> Vec<bool> {
+    (0..len).map(|_| rng.gen_bool(0.5)).collect()
+}
+
+/// `base` with the genes found at the `selected` positions of `order`
+/// rewritten, in place, to follow their order in `order`.
+fn order_based(
+    base: &[usize],
+    order: &[usize],
+    selected: &[bool],
+) -> Vec<usize> {
+    let reordered: HashSet<usize> = order
+        .iter()
+        .zip(selected)
+        .filter(|(_, &selected)| selected)
+        .map(|(&gene, _)| gene)
+        .collect();
+    let mut sorted = order.iter().filter(|gene| reordered.contains(gene));
+    base.iter()
+        .map(|gene| {
+            if reordered.contains(gene) {
+                *sorted.next().expect("order to be a permutation")
+            } else {
+                *gene
+            }
+        })
+        .collect()
+}
+
+/// `donor`'s genes at the `selected` positions, the remaining positions
+/// filled with `filler`'s missing genes in order.
+fn position_based(
+    donor: &[usize],
+    filler: &[usize],
+    selected: &[bool],
+) -> Vec<usize> {
+    let kept: HashSet<usize> = donor
+        .iter()
+        .zip(selected)
+        .filter(|(_, &selected)| selected)
+        .map(|(&gene, _)| gene)
+        .collect();
+    let mut missing = filler.iter().filter(|gene| !kept.contains(gene));
+    donor
+        .iter()
+        .zip(selected)
+        .map(|(&gene, &selected)| {
+            if selected {
+                gene
+            } else {
+                *missing.next().expect("filler to be a permutation")
+            }
+        })
+        .collect()
+}
+
+/// Walks the edge table from `start`, indexes being positions in `genes`.
+/// Ties and dead ends are broken at random.
+fn edge_recombination<R: Rng + ?Sized>(
+    genes: &[usize],
+    mut edges: Vec<Vec<usize>>,
+    start: usize,
+    rng: &mut R,
+) -> Vec<usize> {
+    let mut visited = vec![false; genes.len()];
+    let mut child = Vec::with_capacity(genes.len());
+    let mut current = start;
+    loop {
+        child.push(genes[current]);
+        visited[current] = true;
+        let neighbours = std::mem::take(&mut edges[current]);
+        for &neighbour in &neighbours {
+            edges[neighbour].retain(|&index| index != current);
+        }
+        if child.len() == genes.len() {
+            break;
+        }
+        let fewest_edges = neighbours
+            .iter()
+            .map(|&neighbour| edges[neighbour].len())
+            .min();
+        let candidates: Vec<usize> = match fewest_edges {
+            Some(fewest_edges) => neighbours
+                .into_iter()
+                .filter(|&neighbour| edges[neighbour].len() == fewest_edges)
+                .collect(),
+            None => (0..genes.len()).filter(|&index| !visited[index]).collect(),
+        };
+        current = *candidates.choose(rng).expect("an unvisited gene to remain");
+    }
+    child
+}
+
+/// Crossovers only apply to the chromosome types `CrossoverMethod::suits`,
+/// which `Config::validate` checks.
+fn unsuited(individual: &Individual) -> ! {
+    unreachable!("Crossover does not suit chromosome {individual}")
+}
+
+fn one_point<T: Copy, R: Rng + ?Sized>(
+    genes_1: &[T],
+    genes_2: &[T],
+    rng: &mut R,
+) -> (Vec<T>, Vec<T>) {
+    let crossover_point = rng.gen_range(0..genes_1.len());
+    let genes_iter = genes_1
+        .iter()
+        .take(crossover_point)
+        .zip(genes_2.iter().take(crossover_point));
+    let genes_iter_end = genes_1
+        .iter()
+        .skip(crossover_point)
+        .zip(genes_2.iter().skip(crossover_point));
+    genes_iter
+        .chain(genes_iter_end.map(|(gene_1, gene_2)| (gene_2, gene_1)))
+        .map(|(&gene_1, &gene_2)| (gene_1, gene_2))
+        .unzip()
+}
+
+fn two_points<T: Copy, R: Rng + ?Sized>(
+    genes_1: &[T],
+    genes_2: &[T],
+    rng: &mut R,
+) -> (Vec<T>, Vec<T>) {
+    let crossover_point_1 = rng.gen_range(0..genes_1.len());
+    if crossover_point_1 >= genes_1.len() - 1 {
+        return (genes_1.to_vec(), genes_2.to_vec());
+    }
+    let crossover_point_2 =
+        rng.gen_range((crossover_point_1 + 1)..genes_1.len());
+    let genes_iter_start = genes_1
+        .iter()
+        .take(crossover_point_1)
+        .zip(genes_2.iter().take(crossover_point_1));
+    let genes_iter_middle = genes_1
+        .iter()
+        .skip(crossover_point_1)
+        .take(crossover_point_2 - crossover_point_1)
+        .zip(
+            genes_2
+                .iter()
+                .skip(crossover_point_1)
+                .take(crossover_point_2 - crossover_point_1),
+        );
+    let genes_iter_end = genes_1
+        .iter()
+        .skip(crossover_point_2)
+        .zip(genes_2.iter().skip(crossover_point_2));
+
+    genes_iter_start
+        .chain(genes_iter_middle.map(|(gene_1, gene_2)| (gene_2, gene_1)))
+        .chain(genes_iter_end)
+        .map(|(&gene_1, &gene_2)| (gene_1, gene_2))
+        .unzip()
+}
+
+fn n_points<T: Copy, R: Rng + ?Sized>(
+    genes_1: &[T],
+    genes_2: &[T],
+    points: usize,
+    rng: &mut R,
+) -> (Vec<T>, Vec<T>) {
+    let cuts = genes_1.len().saturating_sub(1);
+    let mut crossover_points: Vec<usize> =
+        index::sample(rng, cuts, points.min(cuts))
+            .into_iter()
+            .map(|cut| cut + 1)
+            .collect();
+    crossover_points.sort_unstable();
+    let mut crossover_points = crossover_points.into_iter().peekable();
+    let mut swapped = false;
+    genes_1
+        .iter()
+        .zip(genes_2)
+        .enumerate()
+        .map(|(i, (&gene_1, &gene_2))| {
+            if crossover_points.next_if_eq(&i).is_some() {
+                swapped = !swapped;
+            }
+            if swapped {
+                (gene_2, gene_1)
+            } else {
+                (gene_1, gene_2)
+            }
+        })
+        .unzip()
+}
+
+fn uniform<T: Copy, R: Rng + ?Sized>(
+    genes_1: &[T],
+    genes_2: &[T],
+    swap_chance: f64,
+    rng: &mut R,
+) -> (Vec<T>, Vec<T>) {
+    genes_1
+        .iter()
+        .zip(genes_2)
+        .map(|(&gene_1, &gene_2)| {
+            if rng.gen_bool(swap_chance) {
+                (gene_2, gene_1)
+            } else {
+                (gene_1, gene_2)
+            }
+        })
+        .unzip()
+}
+
+#[cfg(test)]
+mod tests {
+    use loader_config::PopType;
+
+    use super::*;
+    use crate::RngStream;
+
+    fn real_parents() -> (Individual, Individual) {
+        (
+            Individual::Real(vec![0.0, 1.0, -2.0, 5.0, 3.0]),
+            Individual::Real(vec![1.0, 1.0, 2.0, -5.0, 3.5]),
+        )
+    }
+
+    fn real_genes(individual: &Individual) -> &[f64] {
+        let Individual::Real(genes) = individual else {
+            panic!("{individual} is not a real chromosome");
+        };
+        genes
+    }
+
+    /// Checks every child gene lies within the parents' genes extended by
+    /// `alpha` times their distance on both sides.
+    fn assert_within_parents(crossover: &dyn Crossover, alpha: f64) {
+        let (parent_1, parent_2) = real_parents();
+        for seed in 0..100 {
+            let mut rng = RngStream::new(seed).rng();
+            let (child_1, child_2) =
+                crossover.crossover(&parent_1, &parent_2, &mut rng);
+            for child in [&child_1, &child_2] {
+                let genes = real_genes(child);
+                assert_eq!(genes.len(), real_genes(&parent_1).len());
+                for ((&gene, &gene_1), &gene_2) in genes
+                    .iter()
+                    .zip(real_genes(&parent_1))
+                    .zip(real_genes(&parent_2))
+                {
+                    let extension = alpha * (gene_1 - gene_2).abs();
+                    assert!(gene >= gene_1.min(gene_2) - extension - 1e-12);
+                    assert!(gene <= gene_1.max(gene_2) + extension + 1e-12);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn arithmetic_children_lie_between_parents() {
+        assert_within_parents(&ArithmeticCrossover {}, 0.0);
+    }
+
+    #[test]
+    fn arithmetic_children_sum_to_parents() {
+        let (parent_1, parent_2) = real_parents();
+        let mut rng = RngStream::new(3).rng();
+        let (child_1, child_2) =
+            ArithmeticCrossover {}.crossover(&parent_1, &parent_2, &mut rng);
+        for i in 0..real_genes(&parent_1).len() {
+            let parents = real_genes(&parent_1)[i] + real_genes(&parent_2)[i];
+            let children = real_genes(&child_1)[i] + real_genes(&child_2)[i];
+            assert!((parents - children).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn blx_alpha_children_lie_within_extended_parents() {
+        assert_within_parents(&BlxAlphaCrossover::new(0.5), 0.5);
+        assert_within_parents(&BlxAlphaCrossover::new(0.0), 0.0);
+    }
+
+    #[test]
+    fn real_crossovers_only_suit_real_populations() {
+        for method in [CrossoverMethod::Arithmetic, CrossoverMethod::BlxAlpha] {
+            assert!(method.suits(PopType::Real));
+            for pop_type in
+                [PopType::Binary, PopType::Integer, PopType::Permuted]
+            {
+                assert!(!method.suits(pop_type));
+            }
+        }
+    }
+
+    #[test]
+    fn permutation_crossovers_keep_permutations() {
+        let operators: [Box<dyn Crossover>; 6] = [
+            Box::new(CycleCrossover {}),
+            Box::new(PartiallyMappedCrossover {}),
+            Box::new(OrderCrossover {}),
+            Box::new(OrderBasedCrossover {}),
+            Box::new(PositionBasedCrossover {}),
+            Box::new(EdgeRecombinationCrossover {}),
+        ];
+        let identity: Vec<usize> = (0..10).collect();
+        let parent_1 = Individual::Permuted(identity.clone());
+        let parent_2 = Individual::Permuted(vec![3, 7, 0, 9, 5, 1, 8, 2, 6, 4]);
+        for operator in &operators {
+            for seed in 0..100 {
+                let mut rng = RngStream::new(seed).rng();
+                let (child_1, child_2) =
+                    operator.crossover(&parent_1, &parent_2, &mut rng);
+                for child in [child_1, child_2] {
+                    let Individual::Permuted(mut genes) = child else {
+                        panic!("Crossover changed the chromosome type");
+                    };
+                    genes.sort_unstable();
+                    assert_eq!(genes, identity);
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Crossover does not suit")]
+    fn blx_alpha_rejects_binary_chromosomes() {
+        let parent = Individual::Binary(vec![true, false, true]);
+        let mut rng = RngStream::new(0).rng();
+        BlxAlphaCrossover::new(0.5).crossover(&parent, &parent, &mut rng);
+    }
+}
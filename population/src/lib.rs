@@ -1,19 +1,25 @@
-mod crossover;
+pub mod crossover;
+pub mod mutation;
+mod rng;
+pub mod solution;
 
-use std::fmt::Display;
+use std::{collections::HashMap, fmt::Display};
 
-use crossover::{
-    Crossover, CycleCrossover, OnePointCrossover, PartiallyMappedCrossover,
-    TwoPointsCrossover, UniformCrossover,
-};
-use loader_config::{CrossoverMethod, PopType};
+pub use crossover::{Crossover, CrossoverBuilder, CrossoverRegistry};
+use loader_config::{BoundConfig, PopConfig, PopType};
+pub use mutation::{Mutation, MutationBuilder, MutationRegistry};
 use rand::{prelude::SliceRandom, Rng};
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
+pub use rng::RngStream;
+use serde::{Deserialize, Serialize};
+pub use solution::Solution;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Individual {
     Binary(Vec<bool>),
     Permuted(Vec<usize>),
+    Real(Vec<f64>),
+    Integer(Vec<i64>),
 }
 
 impl Display for Individual {
@@ -21,100 +27,162 @@ impl Display for Individual {
         match self {
             Individual::Binary(value) => write!(f, "{:?}", *value),
             Individual::Permuted(value) => write!(f, "{:?}", *value),
+            Individual::Real(value) => write!(f, "{:?}", *value),
+            Individual::Integer(value) => write!(f, "{:?}", *value),
         }
     }
 }
 
 impl Individual {
+    /// Whether the chromosome has the type and the number of genes of the
+    /// individuals of `pop_config`.
     #[must_use]
-    pub fn mutate(&self, mutation_chance: f64) -> Self {
+    pub fn fits(&self, pop_config: &PopConfig) -> bool {
+        let genes = match (self, pop_config.pop_type) {
+            (Individual::Binary(genes), PopType::Binary) => genes.len(),
+            (Individual::Permuted(genes), PopType::Permuted) => genes.len(),
+            (Individual::Real(genes), PopType::Real) => genes.len(),
+            (Individual::Integer(genes), PopType::Integer) => genes.len(),
+            _ => return false,
+        };
+        genes == pop_config.dim
+    }
+
+    /// Genes of a binary, permutation or integer chromosome as integers,
+    /// `None` for real chromosomes.
+    fn discrete_genes(&self) -> Option<Vec<i64>> {
         match self {
             Individual::Binary(genes) => {
-                let genes_iter = genes.iter();
-
-                Individual::Binary(
-                    genes_iter
-                        .map(|gene| {
-                            let mut rng = rand::thread_rng();
-                            let mutation = rng.gen::<f64>();
-                            if mutation <= mutation_chance {
-                                return !gene;
-                            }
-                            *gene
-                        })
-                        .collect(),
-                )
+                Some(genes.iter().map(|&gene| i64::from(gene)).collect())
             }
             Individual::Permuted(genes) => {
-                let mut new_genes = genes.clone();
-                for i in 0..genes.len() {
-                    let mut rng = rand::thread_rng();
-                    let mutation = rng.gen::<f64>();
-                    if mutation <= mutation_chance {
-                        let new_gene = rng.gen_range(0..genes.len());
-                        (new_genes[i], new_genes[new_gene]) =
-                            (new_genes[new_gene], new_genes[i]);
-                    }
-                }
-                Individual::Permuted(new_genes.clone())
+                Some(genes.iter().map(|&gene| gene as i64).collect())
             }
+            Individual::Integer(genes) => Some(genes.clone()),
+            Individual::Real(_) => None,
         }
     }
 
+    /// # Panics
+    /// If a real or integer population is configured without bounds
     #[must_use]
-    pub fn crossover(
-        &self,
-        parent_2: &Individual,
-        crossover_method: &CrossoverMethod,
-    ) -> (Self, Self) {
-        match crossover_method {
-            CrossoverMethod::OnePoint => {
-                OnePointCrossover::crossover(self, parent_2)
-            }
-            CrossoverMethod::TwoPoints => {
-                TwoPointsCrossover::crossover(self, parent_2)
-            }
-            CrossoverMethod::Uniform => {
-                UniformCrossover::crossover(self, parent_2)
-            }
-            CrossoverMethod::Cycle => CycleCrossover::crossover(self, parent_2),
-            CrossoverMethod::PartiallyMapped => {
-                PartiallyMappedCrossover::crossover(self, parent_2)
-            }
-        }
-    }
-
-    #[must_use]
-    pub fn new(dim: usize, individual_type: &PopType) -> Self {
-        let mut rng = rand::thread_rng();
-        let chromosome: Individual = match individual_type {
+    pub fn new<R: Rng + ?Sized>(pop_config: &PopConfig, rng: &mut R) -> Self {
+        let dim = pop_config.dim;
+        let chromosome: Individual = match pop_config.pop_type {
             PopType::Binary => Individual::Binary(
                 (0..dim).map(|_| rng.gen::<bool>()).collect(),
             ),
             PopType::Permuted => {
                 let mut genes = (0..dim).collect::<Vec<usize>>();
-                genes.shuffle(&mut rng);
+                genes.shuffle(rng);
                 Individual::Permuted(genes)
             }
-            PopType::Real => todo!(),
-            PopType::Integer => todo!(),
+            PopType::Real => {
+                let bounds =
+                    pop_config.bounds.expect("Real populations require bounds");
+                Individual::Real(
+                    (0..dim)
+                        .map(|_| rng.gen_range(bounds.lower..=bounds.upper))
+                        .collect(),
+                )
+            }
+            PopType::Integer => {
+                let bounds = pop_config
+                    .bounds
+                    .expect("Integer populations require bounds");
+                let (lower, upper) = integer_bounds(&bounds);
+                Individual::Integer(
+                    (0..dim).map(|_| rng.gen_range(lower..=upper)).collect(),
+                )
+            }
         };
         chromosome
     }
 }
 
-#[derive(Debug, Clone)]
+/// Rounds the bounds inwards so every integer gene stays inside them.
+pub(crate) fn integer_bounds(bounds: &BoundConfig) -> (i64, i64) {
+    (bounds.lower.ceil() as i64, bounds.upper.floor() as i64)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Population {
     pub individuals: Vec<Individual>,
 }
 
 impl Population {
     #[must_use]
-    pub fn new(qtd_individuals: usize, dim: usize, pop_type: &PopType) -> Self {
-        let individuals: Vec<Individual> = (0..qtd_individuals)
+    pub fn new(pop_config: &PopConfig, rng_stream: &RngStream) -> Self {
+        let individuals: Vec<Individual> = (0..pop_config.pop_size)
             .into_par_iter()
-            .map(|_| Individual::new(dim, pop_type))
+            .map(|i| {
+                let mut rng = rng_stream.fork(i as u64).rng();
+                Individual::new(pop_config, &mut rng)
+            })
             .collect();
         Population { individuals }
     }
+
+    /// Diversity within `[0, 1]`: the mean fraction of individuals whose
+    /// gene differs from the most common one at its position, or for real
+    /// chromosomes the mean gene standard deviation relative to half the
+    /// bounds range.
+    #[must_use]
+    pub fn diversity(&self, bounds: Option<&BoundConfig>) -> f64 {
+        let pop_size = self.individuals.len() as f64;
+        let columns = match self.individuals.first() {
+            Some(Individual::Real(genes)) => genes.len(),
+            Some(Individual::Binary(genes)) => genes.len(),
+            Some(Individual::Permuted(genes)) => genes.len(),
+            Some(Individual::Integer(genes)) => genes.len(),
+            None => 0,
+        };
+        if columns == 0 {
+            return 0.0;
+        }
+        let spread: f64 = match &self.individuals[0] {
+            Individual::Real(_) => {
+                let half_range = bounds
+                    .map_or(1.0, |bounds| (bounds.upper - bounds.lower) / 2.0);
+                let genes: Vec<&Vec<f64>> = self
+                    .individuals
+                    .iter()
+                    .filter_map(|individual| match individual {
+                        Individual::Real(genes) => Some(genes),
+                        _ => None,
+                    })
+                    .collect();
+                (0..columns)
+                    .map(|column| {
+                        let mean = genes.iter().map(|g| g[column]).sum::<f64>()
+                            / pop_size;
+                        let variance = genes
+                            .iter()
+                            .map(|g| (g[column] - mean).powi(2))
+                            .sum::<f64>()
+                            / pop_size;
+                        (variance.sqrt() / half_range).min(1.0)
+                    })
+                    .sum()
+            }
+            _ => {
+                let genes: Vec<Vec<i64>> = self
+                    .individuals
+                    .iter()
+                    .filter_map(Individual::discrete_genes)
+                    .collect();
+                (0..columns)
+                    .map(|column| {
+                        let mut counts: HashMap<i64, usize> = HashMap::new();
+                        for individual in &genes {
+                            *counts.entry(individual[column]).or_default() += 1;
+                        }
+                        let mode = counts.values().copied().max().unwrap_or(0);
+                        1.0 - mode as f64 / pop_size
+                    })
+                    .sum()
+            }
+        };
+        spread / columns as f64
+    }
 }
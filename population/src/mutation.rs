@@ -0,0 +1,735 @@
+use std::collections::HashMap;
+
+use loader_config::{BoundConfig, Config, MutationMethod};
+use rand::{seq::SliceRandom, Rng, RngCore};
+use rand_distr::{Distribution, Normal};
+
+use crate::{integer_bounds, Individual};
+
+/// Fraction of the bounds range used as the standard deviation of the
+/// gaussian perturbation applied to real genes.
+const REAL_MUTATION_SIGMA: f64 = 0.1;
+
+/// Fraction of the bounds range used as the largest creep step applied to
+/// integer genes.
+const INTEGER_CREEP_STEP: f64 = 0.1;
+
+/// Distribution index of the polynomial mutation, higher values keep the
+/// children closer to their parents.
+const POLYNOMIAL_ETA: f64 = 20.0;
+
+/// Mutation operator. Every gene is mutated with probability
+/// `mutation_chance`; permutation operators use the chosen gene as one end
+/// of the moved, reversed or shuffled segment.
+pub trait Mutation {
+    fn mutate(
+        &self,
+        individual: &Individual,
+        rng: &mut dyn RngCore,
+    ) -> Individual;
+}
+
+/// Builds a mutation operator from the run config.
+pub type MutationBuilder =
+    Box<dyn Fn(&Config) -> Box<dyn Mutation + Send + Sync> + Send + Sync>;
+
+/// Mutation operators by [`MutationMethod::name`]. The default registry
+/// holds every built-in method; downstream crates register their own
+/// operators and select them with `MutationMethod::Custom`.
+pub struct MutationRegistry {
+    builders: HashMap<String, MutationBuilder>,
+}
+
+impl MutationRegistry {
+    /// Registry without any operator.
+    #[must_use]
+    pub fn empty() -> Self {
+        MutationRegistry {
+            builders: HashMap::new(),
+        }
+    }
+
+    /// Registers `builder` under `name`, replacing any operator already
+    /// registered with it.
+    pub fn register<F>(&mut self, name: &str, builder: F)
+    where
+        F: Fn(&Config) -> Box<dyn Mutation + Send + Sync>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.builders.insert(name.to_string(), Box::new(builder));
+    }
+
+    /// Builds the operator selected by `config.mutation_method`, or the
+    /// default of the population type, `None` if no operator is registered
+    /// under its name.
+    #[must_use]
+    pub fn build(
+        &self,
+        config: &Config,
+    ) -> Option<Box<dyn Mutation + Send + Sync>> {
+        self.builders
+            .get(mutation_method(config).name())
+            .map(|builder| builder(config))
+    }
+}
+
+impl Default for MutationRegistry {
+    fn default() -> Self {
+        let mut registry = MutationRegistry::empty();
+        let builtins: [(MutationMethod, MutationBuilder); 11] = [
+            (
+                MutationMethod::BitFlip,
+                Box::new(|config| {
+                    Box::new(BitFlipMutation::new(config.mutation_chance))
+                }),
+            ),
+            (
+                MutationMethod::Swap,
+                Box::new(|config| {
+                    Box::new(SwapMutation::new(config.mutation_chance))
+                }),
+            ),
+            (
+                MutationMethod::Insertion,
+                Box::new(|config| {
+                    Box::new(InsertionMutation::new(config.mutation_chance))
+                }),
+            ),
+            (
+                MutationMethod::Inversion,
+                Box::new(|config| {
+                    Box::new(InversionMutation::new(config.mutation_chance))
+                }),
+            ),
+            (
+                MutationMethod::Scramble,
+                Box::new(|config| {
+                    Box::new(ScrambleMutation::new(config.mutation_chance))
+                }),
+            ),
+            (
+                MutationMethod::Displacement,
+                Box::new(|config| {
+                    Box::new(DisplacementMutation::new(config.mutation_chance))
+                }),
+            ),
+            (
+                MutationMethod::Gaussian,
+                Box::new(|config| {
+                    Box::new(GaussianMutation::new(
+                        config.mutation_chance,
+                        config.pop_config.bounds,
+                    ))
+                }),
+            ),
+            (
+                MutationMethod::Polynomial,
+                Box::new(|config| {
+                    Box::new(PolynomialMutation::new(
+                        config.mutation_chance,
+                        config.pop_config.bounds,
+                    ))
+                }),
+            ),
+            (
+                MutationMethod::Uniform,
+                Box::new(|config| {
+                    Box::new(UniformMutation::new(
+                        config.mutation_chance,
+                        config.pop_config.bounds,
+                    ))
+                }),
+            ),
+            (
+                MutationMethod::Creep,
+                Box::new(|config| {
+                    Box::new(CreepMutation::new(
+                        config.mutation_chance,
+                        config.pop_config.bounds,
+                    ))
+                }),
+            ),
+            (
+                MutationMethod::RandomReset,
+                Box::new(|config| {
+                    Box::new(RandomResetMutation::new(
+                        config.mutation_chance,
+                        config.pop_config.bounds,
+                    ))
+                }),
+            ),
+        ];
+        for (method, builder) in builtins {
+            registry.builders.insert(method.name().to_string(), builder);
+        }
+        registry
+    }
+}
+
+/// The configured mutation, or the default of the population type.
+fn mutation_method(config: &Config) -> MutationMethod {
+    config
+        .mutation_method
+        .clone()
+        .unwrap_or_else(|| config.pop_config.pop_type.default_mutation())
+}
+
+pub struct BitFlipMutation {
+    mutation_chance: f64,
+}
+
+impl BitFlipMutation {
+    #[must_use]
+    pub fn new(mutation_chance: f64) -> Self {
+        BitFlipMutation { mutation_chance }
+    }
+}
+
+impl Mutation for BitFlipMutation {
+    fn mutate(
+        &self,
+        individual: &Individual,
+        rng: &mut dyn RngCore,
+    ) -> Individual {
+        let mutation_chance = self.mutation_chance;
+        match individual {
+            Individual::Binary(genes) => Individual::Binary(
+                genes
+                    .iter()
+                    .map(|gene| {
+                        let mutation = rng.gen::<f64>();
+                        if mutation <= mutation_chance {
+                            return !gene;
+                        }
+                        *gene
+                    })
+                    .collect(),
+            ),
+            _ => unsuited(individual),
+        }
+    }
+}
+
+/// Swaps the chosen gene with a random one.
+pub struct SwapMutation {
+    mutation_chance: f64,
+}
+
+impl SwapMutation {
+    #[must_use]
+    pub fn new(mutation_chance: f64) -> Self {
+        SwapMutation { mutation_chance }
+    }
+}
+
+impl Mutation for SwapMutation {
+    fn mutate(
+        &self,
+        individual: &Individual,
+        rng: &mut dyn RngCore,
+    ) -> Individual {
+        let mutation_chance = self.mutation_chance;
+        match individual {
+            Individual::Permuted(genes) => {
+                Individual::Permuted(mutate_permutation(
+                    genes,
+                    mutation_chance,
+                    rng,
+                    |genes, i, rng| {
+                        let j = rng.gen_range(0..genes.len());
+                        genes.swap(i, j);
+                    },
+                ))
+            }
+            _ => unsuited(individual),
+        }
+    }
+}
+
+/// Moves the chosen gene to a random position.
+pub struct InsertionMutation {
+    mutation_chance: f64,
+}
+
+impl InsertionMutation {
+    #[must_use]
+    pub fn new(mutation_chance: f64) -> Self {
+        InsertionMutation { mutation_chance }
+    }
+}
+
+impl Mutation for InsertionMutation {
+    fn mutate(
+        &self,
+        individual: &Individual,
+        rng: &mut dyn RngCore,
+    ) -> Individual {
+        let mutation_chance = self.mutation_chance;
+        match individual {
+            Individual::Permuted(genes) => {
+                Individual::Permuted(mutate_permutation(
+                    genes,
+                    mutation_chance,
+                    rng,
+                    |genes, i, rng| {
+                        let gene = genes.remove(i);
+                        let j = rng.gen_range(0..=genes.len());
+                        genes.insert(j, gene);
+                    },
+                ))
+            }
+            _ => unsuited(individual),
+        }
+    }
+}
+
+/// Reverses the segment between the chosen gene and a random one.
+pub struct InversionMutation {
+    mutation_chance: f64,
+}
+
+impl InversionMutation {
+    #[must_use]
+    pub fn new(mutation_chance: f64) -> Self {
+        InversionMutation { mutation_chance }
+    }
+}
+
+impl Mutation for InversionMutation {
+    fn mutate(
+        &self,
+        individual: &Individual,
+        rng: &mut dyn RngCore,
+    ) -> Individual {
+        let mutation_chance = self.mutation_chance;
+        match individual {
+            Individual::Permuted(genes) => {
+                Individual::Permuted(mutate_permutation(
+                    genes,
+                    mutation_chance,
+                    rng,
+                    |genes, i, rng| {
+                        let (start, end) = segment(genes.len(), i, rng);
+                        genes[start..end].reverse();
+                    },
+                ))
+            }
+            _ => unsuited(individual),
+        }
+    }
+}
+
+/// Shuffles the segment between the chosen gene and a random one.
+pub struct ScrambleMutation {
+    mutation_chance: f64,
+}
+
+impl ScrambleMutation {
+    #[must_use]
+    pub fn new(mutation_chance: f64) -> Self {
+        ScrambleMutation { mutation_chance }
+    }
+}
+
+impl Mutation for ScrambleMutation {
+    fn mutate(
+        &self,
+        individual: &Individual,
+        rng: &mut dyn RngCore,
+    ) -> Individual {
+        let mutation_chance = self.mutation_chance;
+        match individual {
+            Individual::Permuted(genes) => {
+                Individual::Permuted(mutate_permutation(
+                    genes,
+                    mutation_chance,
+                    rng,
+                    |genes, i, rng| {
+                        let (start, end) = segment(genes.len(), i, rng);
+                        genes[start..end].shuffle(rng);
+                    },
+                ))
+            }
+            _ => unsuited(individual),
+        }
+    }
+}
+
+/// Moves the segment between the chosen gene and a random one to a random
+/// position of the remaining genes.
+pub struct DisplacementMutation {
+    mutation_chance: f64,
+}
+
+impl DisplacementMutation {
+    #[must_use]
+    pub fn new(mutation_chance: f64) -> Self {
+        DisplacementMutation { mutation_chance }
+    }
+}
+
+impl Mutation for DisplacementMutation {
+    fn mutate(
+        &self,
+        individual: &Individual,
+        rng: &mut dyn RngCore,
+    ) -> Individual {
+        let mutation_chance = self.mutation_chance;
+        match individual {
+            Individual::Permuted(genes) => {
+                Individual::Permuted(mutate_permutation(
+                    genes,
+                    mutation_chance,
+                    rng,
+                    |genes, i, rng| {
+                        let (start, end) = segment(genes.len(), i, rng);
+                        let moved: Vec<usize> =
+                            genes.drain(start..end).collect();
+                        let j = rng.gen_range(0..=genes.len());
+                        genes.splice(j..j, moved);
+                    },
+                ))
+            }
+            _ => unsuited(individual),
+        }
+    }
+}
+
+/// Adds gaussian noise with `REAL_MUTATION_SIGMA` times the bounds range as
+/// standard deviation.
+pub struct GaussianMutation {
+    mutation_chance: f64,
+    bounds: Option<BoundConfig>,
+}
+
+impl GaussianMutation {
+    #[must_use]
+    pub fn new(mutation_chance: f64, bounds: Option<BoundConfig>) -> Self {
+        GaussianMutation {
+            mutation_chance,
+            bounds,
+        }
+    }
+}
+
+impl Mutation for GaussianMutation {
+    fn mutate(
+        &self,
+        individual: &Individual,
+        rng: &mut dyn RngCore,
+    ) -> Individual {
+        let mutation_chance = self.mutation_chance;
+        let bounds = self.bounds.as_ref();
+        match individual {
+            Individual::Real(genes) => {
+                let bounds =
+                    bounds.expect("Real individuals require bounds to mutate");
+                let sigma = (bounds.upper - bounds.lower) * REAL_MUTATION_SIGMA;
+                let normal = Normal::new(0.0, sigma)
+                    .expect("Failed to build gaussian distribution");
+                Individual::Real(mutate_real(
+                    genes,
+                    mutation_chance,
+                    bounds,
+                    rng,
+                    |gene, rng| gene + normal.sample(rng),
+                ))
+            }
+            _ => unsuited(individual),
+        }
+    }
+}
+
+/// Deb's polynomial mutation: the perturbation follows a polynomial
+/// distribution of index `POLYNOMIAL_ETA` scaled by the bounds range.
+pub struct PolynomialMutation {
+    mutation_chance: f64,
+    bounds: Option<BoundConfig>,
+}
+
+impl PolynomialMutation {
+    #[must_use]
+    pub fn new(mutation_chance: f64, bounds: Option<BoundConfig>) -> Self {
+        PolynomialMutation {
+            mutation_chance,
+            bounds,
+        }
+    }
+}
+
+impl Mutation for PolynomialMutation {
+    fn mutate(
+        &self,
+        individual: &Individual,
+        rng: &mut dyn RngCore,
+    ) -> Individual {
+        let mutation_chance = self.mutation_chance;
+        let bounds = self.bounds.as_ref();
+        match individual {
+            Individual::Real(genes) => {
+                let bounds =
+                    bounds.expect("Real individuals require bounds to mutate");
+                let range = bounds.upper - bounds.lower;
+                let exponent = 1.0 / (POLYNOMIAL_ETA + 1.0);
+                Individual::Real(mutate_real(
+                    genes,
+                    mutation_chance,
+                    bounds,
+                    rng,
+                    |gene, rng| {
+                        let u = rng.gen::<f64>();
+                        let delta = if u < 0.5 {
+                            (2.0 * u).powf(exponent) - 1.0
+                        } else {
+                            1.0 - (2.0 * (1.0 - u)).powf(exponent)
+                        };
+                        gene + delta * range
+                    },
+                ))
+            }
+            _ => unsuited(individual),
+        }
+    }
+}
+
+/// Resets the gene to a uniform value within the bounds.
+pub struct UniformMutation {
+    mutation_chance: f64,
+    bounds: Option<BoundConfig>,
+}
+
+impl UniformMutation {
+    #[must_use]
+    pub fn new(mutation_chance: f64, bounds: Option<BoundConfig>) -> Self {
+        UniformMutation {
+            mutation_chance,
+            bounds,
+        }
+    }
+}
+
+impl Mutation for UniformMutation {
+    fn mutate(
+        &self,
+        individual: &Individual,
+        rng: &mut dyn RngCore,
+    ) -> Individual {
+        let mutation_chance = self.mutation_chance;
+        let bounds = self.bounds.as_ref();
+        match individual {
+            Individual::Real(genes) => {
+                let bounds =
+                    bounds.expect("Real individuals require bounds to mutate");
+                Individual::Real(mutate_real(
+                    genes,
+                    mutation_chance,
+                    bounds,
+                    rng,
+                    |_, rng| rng.gen_range(bounds.lower..=bounds.upper),
+                ))
+            }
+            _ => unsuited(individual),
+        }
+    }
+}
+
+/// Creeps the gene towards a neighbour, at most `INTEGER_CREEP_STEP` times
+/// the bounds range away.
+pub struct CreepMutation {
+    mutation_chance: f64,
+    bounds: Option<BoundConfig>,
+}
+
+impl CreepMutation {
+    #[must_use]
+    pub fn new(mutation_chance: f64, bounds: Option<BoundConfig>) -> Self {
+        CreepMutation {
+            mutation_chance,
+            bounds,
+        }
+    }
+}
+
+impl Mutation for CreepMutation {
+    fn mutate(
+        &self,
+        individual: &Individual,
+        rng: &mut dyn RngCore,
+    ) -> Individual {
+        let mutation_chance = self.mutation_chance;
+        let bounds = self.bounds.as_ref();
+        match individual {
+            Individual::Integer(genes) => {
+                let bounds = bounds
+                    .expect("Integer individuals require bounds to mutate");
+                let (lower, upper) = integer_bounds(bounds);
+                let step = ((upper - lower) as f64 * INTEGER_CREEP_STEP)
+                    .ceil()
+                    .max(1.0) as i64;
+                Individual::Integer(
+                    genes
+                        .iter()
+                        .map(|&gene| {
+                            let mutation = rng.gen::<f64>();
+                            if mutation > mutation_chance {
+                                return gene.clamp(lower, upper);
+                            }
+                            let creep = rng.gen_range(1..=step);
+                            let creep =
+                                if rng.gen_bool(0.5) { creep } else { -creep };
+                            (gene + creep).clamp(lower, upper)
+                        })
+                        .collect(),
+                )
+            }
+            _ => unsuited(individual),
+        }
+    }
+}
+
+/// Resets the gene to a random value within the bounds.
+pub struct RandomResetMutation {
+    mutation_chance: f64,
+    bounds: Option<BoundConfig>,
+}
+
+impl RandomResetMutation {
+    #[must_use]
+    pub fn new(mutation_chance: f64, bounds: Option<BoundConfig>) -> Self {
+        RandomResetMutation {
+            mutation_chance,
+            bounds,
+        }
+    }
+}
+
+impl Mutation for RandomResetMutation {
+    fn mutate(
+        &self,
+        individual: &Individual,
+        rng: &mut dyn RngCore,
+    ) -> Individual {
+        let mutation_chance = self.mutation_chance;
+        let bounds = self.bounds.as_ref();
+        match individual {
+            Individual::Integer(genes) => {
+                let bounds = bounds
+                    .expect("Integer individuals require bounds to mutate");
+                let (lower, upper) = integer_bounds(bounds);
+                Individual::Integer(
+                    genes
+                        .iter()
+                        .map(|&gene| {
+                            let mutation = rng.gen::<f64>();
+                            if mutation > mutation_chance {
+                                return gene.clamp(lower, upper);
+                            }
+                            rng.gen_range(lower..=upper)
+                        })
+                        .collect(),
+                )
+            }
+            _ => unsuited(individual),
+        }
+    }
+}
+
+/// Mutations only apply to the chromosome types `MutationMethod::suits`,
+/// which `Config::validate` checks.
+fn unsuited(individual: &Individual) -> ! {
+    unreachable!("Mutation does not suit chromosome {individual}")
+}
+
+/// Applies `operator` at every position drawn with `mutation_chance`.
+fn mutate_permutation<R, F>(
+    genes: &[usize],
+    mutation_chance: f64,
+    rng: &mut R,
+    operator: F,
+) -> Vec<usize>
+where
+    R: Rng + ?Sized,
+    F: Fn(&mut Vec<usize>, usize, &mut R),
+{
+    let mut new_genes = genes.to_vec();
+    for i in 0..genes.len() {
+        let mutation = rng.gen::<f64>();
+        if mutation <= mutation_chance {
+            operator(&mut new_genes, i, rng);
+        }
+    }
+    new_genes
+}
+
+/// Applies `operator` to the genes drawn with `mutation_chance`, clamping
+/// every gene to the bounds.
+fn mutate_real<R, F>(
+    genes: &[f64],
+    mutation_chance: f64,
+    bounds: &BoundConfig,
+    rng: &mut R,
+    operator: F,
+) -> Vec<f64>
+where
+    R: Rng + ?Sized,
+    F: Fn(f64, &mut R) -> f64,
+{
+    genes
+        .iter()
+        .map(|&gene| {
+            let mutation = rng.gen::<f64>();
+            let gene = if mutation <= mutation_chance {
+                operator(gene, rng)
+            } else {
+                gene
+            };
+            gene.clamp(bounds.lower, bounds.upper)
+        })
+        .collect()
+}
+
+/// Half-open segment between `i` and a random position, both included.
+fn segment<R: Rng + ?Sized>(
+    len: usize,
+    i: usize,
+    rng: &mut R,
+) -> (usize, usize) {
+    let j = rng.gen_range(0..len);
+    (i.min(j), i.max(j) + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RngStream;
+
+    #[test]
+    fn permutation_mutations_keep_permutations() {
+        let operators: [Box<dyn Mutation>; 5] = [
+            Box::new(SwapMutation::new(0.2)),
+            Box::new(InsertionMutation::new(0.2)),
+            Box::new(InversionMutation::new(0.2)),
+            Box::new(ScrambleMutation::new(0.2)),
+            Box::new(DisplacementMutation::new(0.2)),
+        ];
+        let identity: Vec<usize> = (0..10).collect();
+        let individual = Individual::Permuted(identity.clone());
+        for operator in &operators {
+            let mut mutated = false;
+            for seed in 0..100 {
+                let mut rng = RngStream::new(seed).rng();
+                let Individual::Permuted(mut genes) =
+                    operator.mutate(&individual, &mut rng)
+                else {
+                    panic!("Mutation changed the chromosome type");
+                };
+                mutated |= genes != identity;
+                genes.sort_unstable();
+                assert_eq!(genes, identity);
+            }
+            assert!(mutated);
+        }
+    }
+}
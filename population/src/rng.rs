@@ -0,0 +1,47 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Seed of a reproducible random stream.
+///
+/// Streams are forked by key (run, generation, stage, individual index), so
+/// every unit of work draws from its own generator and the results do not
+/// depend on how rayon schedules it across threads.
+#[derive(Clone, Copy, Debug)]
+pub struct RngStream {
+    seed: u64,
+}
+
+impl RngStream {
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        RngStream { seed }
+    }
+
+    /// Draws a fresh seed from the thread RNG, for unseeded runs.
+    #[must_use]
+    pub fn from_entropy() -> Self {
+        RngStream::new(rand::thread_rng().gen())
+    }
+
+    #[must_use]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    #[must_use]
+    pub fn fork(&self, key: u64) -> Self {
+        RngStream::new(splitmix64(self.seed ^ splitmix64(key)))
+    }
+
+    #[must_use]
+    pub fn rng(&self) -> ChaCha8Rng {
+        ChaCha8Rng::seed_from_u64(self.seed)
+    }
+}
+
+fn splitmix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
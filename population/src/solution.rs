@@ -0,0 +1,45 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use loader_config::Config;
+use serde::{Deserialize, Serialize};
+
+use crate::Individual;
+
+/// Best individual of a run, with what is needed to evaluate it again.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Solution {
+    /// Problem name, as given to `problem_factory`.
+    pub problem: String,
+    pub instance: String,
+    /// SHA-256 of the instance file, in hex.
+    pub instance_hash: String,
+    /// The config of the run, with its seed set.
+    pub config: Config,
+    pub run: usize,
+    pub chromosome: Individual,
+    pub fitness: f64,
+    pub decoded: Vec<f64>,
+    pub objective: f64,
+    pub constraint: f64,
+}
+
+impl Solution {
+    /// # Errors
+    /// If the file cannot be read or is not a solution
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    /// # Errors
+    /// If the file cannot be written
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writer.flush()
+    }
+}
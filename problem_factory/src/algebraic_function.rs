@@ -1,13 +1,16 @@
-use std::{
-    fs::File,
-    io::{self, BufRead},
-    path::Path,
-};
+use std::path::Path;
 
-use loader_config::Config;
+use loader_config::{BoundConfig, PopType};
 use population::Individual;
 
-use crate::Problem;
+use crate::{
+    error::ProblemError,
+    reader::{field, read_rows},
+    Problem,
+};
+
+/// Bits encoding `x` in binary individuals.
+const BITS: i32 = 16;
 
 pub struct ProblemAlgebraicFunction {
     max_y: f64,
@@ -17,13 +20,12 @@ pub struct ProblemAlgebraicFunction {
 }
 
 pub struct AlgebraicFunction {
-    config: Config,
     problem: ProblemAlgebraicFunction,
 }
 
 impl AlgebraicFunction {
-    pub fn new(problem: ProblemAlgebraicFunction, config: Config) -> Self {
-        AlgebraicFunction { config, problem }
+    pub fn new(problem: ProblemAlgebraicFunction) -> Self {
+        AlgebraicFunction { problem }
     }
 }
 
@@ -33,34 +35,39 @@ impl Problem for AlgebraicFunction {
             Individual::Binary(value) => {
                 value.iter().map(|&v| f64::from(u32::from(v)))
             }
-            Individual::Permuted(_) => todo!(),
+            Individual::Real(value) => return vec![value[0]],
+            Individual::Permuted(_) | Individual::Integer(_) => todo!(),
         }
         .fold(0.0, |a, b| 2.0 * a + b);
         let value = self.problem.min_x
             + ((self.problem.max_x - self.problem.min_x)
-                * (decimal / (2.0_f64.powf(16.0) - 1.0)));
+                * (decimal / (2.0_f64.powi(BITS) - 1.0)));
         vec![value]
     }
 
-    fn get_config(&self) -> &Config {
-        &self.config
-    }
-
     fn normed_objective(&self, individual: &[f64]) -> f64 {
         (self.objective(individual) - self.problem.min_y)
             / (self.problem.max_y - self.problem.min_y)
     }
 
-    fn constraint(&self, _: &[f64]) -> f64 {
-        0.0
+    /// `x` as `BITS` bits, or as a single real gene.
+    fn supports(&self, pop_type: PopType) -> bool {
+        matches!(pop_type, PopType::Binary | PopType::Real)
     }
 
-    fn fitness(&self, individual: &Individual) -> f64 {
-        let config = self.get_config();
-        let decoded_individual = self.decode(individual);
-        let obj = self.normed_objective(&decoded_individual);
-        let constraint = self.constraint(&decoded_individual);
-        obj + config.constraint_penalty * constraint
+    fn dimension(&self, pop_type: PopType) -> Option<usize> {
+        match pop_type {
+            PopType::Binary => Some(BITS as usize),
+            PopType::Real => Some(1),
+            PopType::Integer | PopType::Permuted => None,
+        }
+    }
+
+    fn bounds(&self) -> Option<BoundConfig> {
+        Some(BoundConfig {
+            lower: self.problem.min_x,
+            upper: self.problem.max_x,
+        })
     }
 
     fn objective(&self, individual: &[f64]) -> f64 {
@@ -74,31 +81,20 @@ impl Problem for AlgebraicFunction {
     }
 }
 
-pub fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
+/// # Errors
+/// If the file cannot be read or is missing any of the function bounds
+pub fn load_instance<P>(
+    filename: P,
+) -> Result<ProblemAlgebraicFunction, ProblemError>
 where
     P: AsRef<Path>,
 {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
-pub fn load_instance<P>(filename: P) -> io::Result<ProblemAlgebraicFunction>
-where
-    P: AsRef<Path>,
-{
-    let problem = read_lines(filename)?
-        .map(|line| {
-            let line = line.expect("Unable to read line");
-            return line
-                .split_whitespace()
-                .map(|i| i.parse().expect("Unable to parse number"))
-                .collect();
-        })
-        .collect::<Vec<Vec<f64>>>();
+    let filename = filename.as_ref();
+    let problem = read_rows::<f64, _>(filename)?;
     Ok(ProblemAlgebraicFunction {
-        min_x: problem[0][0],
-        max_x: problem[0][1],
-        min_y: problem[1][0],
-        max_y: problem[1][1],
+        min_x: field(&problem, filename, 0, 0, "min x")?,
+        max_x: field(&problem, filename, 0, 1, "max x")?,
+        min_y: field(&problem, filename, 1, 0, "min y")?,
+        max_y: field(&problem, filename, 1, 1, "max y")?,
     })
 }
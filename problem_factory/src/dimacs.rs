@@ -0,0 +1,176 @@
+use std::{ops::RangeInclusive, path::Path};
+
+use crate::{
+    error::ProblemError,
+    reader::{parse_error, parse_token, read_lines, tokens},
+};
+
+/// Token of a DIMACS file with its line and column.
+pub(crate) type Located<T> = (usize, usize, T);
+
+/// Numbers following `p <format>` on the problem line.
+pub(crate) struct Header {
+    pub line: usize,
+    pub values: Vec<Located<i64>>,
+}
+
+/// Raw content of a DIMACS file, validated by each format's loader.
+/// Clauses are kept with the line of their terminating `0`.
+pub(crate) struct Dimacs {
+    pub header: Option<Header>,
+    pub clauses: Vec<(usize, Vec<Located<i64>>)>,
+}
+
+/// Reads a DIMACS file: `c` comment lines, an optional `p <format> ...`
+/// problem line and `0` terminated clauses, possibly spanning lines, up to
+/// the end of file or a `%` line.
+pub(crate) fn read_dimacs(
+    path: &Path,
+    format: &str,
+) -> Result<Dimacs, ProblemError> {
+    let io_error = |source| ProblemError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+
+    let mut header: Option<Header> = None;
+    let mut clauses: Vec<(usize, Vec<Located<i64>>)> = Vec::new();
+    let mut clause: Vec<Located<i64>> = Vec::new();
+    let mut line_number = 0;
+    for (line_index, line) in read_lines(path).map_err(io_error)?.enumerate() {
+        let line = line.map_err(io_error)?;
+        line_number = line_index + 1;
+        let trimmed = line.trim_start();
+        if trimmed.starts_with('%') {
+            break;
+        }
+        if trimmed.is_empty() || trimmed.starts_with('c') {
+            continue;
+        }
+        if trimmed.starts_with('p') {
+            let fields: Vec<(usize, &str)> = tokens(&line).collect();
+            let (column, token) = fields[0];
+            if header.is_some() {
+                return Err(parse_error(
+                    path,
+                    line_number,
+                    column,
+                    token,
+                    String::from("duplicated problem line"),
+                ));
+            }
+            if fields.get(1).map(|&(_, token)| token) != Some(format) {
+                return Err(parse_error(
+                    path,
+                    line_number,
+                    column,
+                    trimmed,
+                    format!("expected a `p {format}` problem line"),
+                ));
+            }
+            let values = fields[2..]
+                .iter()
+                .map(|&(column, token)| {
+                    let value =
+                        parse_token(path, line_number, (column, token))?;
+                    Ok((line_number, column, value))
+                })
+                .collect::<Result<_, ProblemError>>()?;
+            header = Some(Header {
+                line: line_number,
+                values,
+            });
+            continue;
+        }
+        for (column, token) in tokens(&line) {
+            let value: i64 = parse_token(path, line_number, (column, token))?;
+            if value == 0 {
+                clauses.push((line_number, std::mem::take(&mut clause)));
+                continue;
+            }
+            clause.push((line_number, column, value));
+        }
+    }
+    if !clause.is_empty() {
+        clauses.push((line_number, clause));
+    }
+    Ok(Dimacs { header, clauses })
+}
+
+/// Checks the problem line matches `usage`, returning its numbers.
+pub(crate) fn header_values(
+    path: &Path,
+    header: &Header,
+    usage: &str,
+    counts: RangeInclusive<usize>,
+) -> Result<Vec<Located<u64>>, ProblemError> {
+    if !counts.contains(&header.values.len()) {
+        return Err(parse_error(
+            path,
+            header.line,
+            1,
+            "p",
+            format!("expected `{usage}`"),
+        ));
+    }
+    header
+        .values
+        .iter()
+        .map(|&(line, column, value)| {
+            let token = value.to_string();
+            let value = u64::try_from(value).map_err(|error| {
+                parse_error(path, line, column, &token, error.to_string())
+            })?;
+            Ok((line, column, value))
+        })
+        .collect()
+}
+
+/// Converts located literals to `i32`, checking they are declared.
+pub(crate) fn literals(
+    path: &Path,
+    clause: &[Located<i64>],
+    variables: Option<usize>,
+) -> Result<Vec<i32>, ProblemError> {
+    clause
+        .iter()
+        .map(|&(line, column, value)| {
+            let token = value.to_string();
+            let literal = i32::try_from(value).map_err(|error| {
+                parse_error(path, line, column, &token, error.to_string())
+            })?;
+            match variables {
+                Some(variables)
+                    if literal.unsigned_abs() as usize > variables =>
+                {
+                    Err(parse_error(
+                        path,
+                        line,
+                        column,
+                        &token,
+                        format!("variable above the declared {variables}"),
+                    ))
+                }
+                _ => Ok(literal),
+            }
+        })
+        .collect()
+}
+
+/// Fails when the problem line declares a different number of clauses.
+pub(crate) fn check_clause_count(
+    path: &Path,
+    (line, column, declared): Located<u64>,
+    found: usize,
+) -> Result<(), ProblemError> {
+    if declared as usize == found {
+        return Ok(());
+    }
+    Err(parse_error(
+        path,
+        line,
+        column,
+        &declared.to_string(),
+        format!("header declares {declared} clauses, found {found}"),
+    ))
+}
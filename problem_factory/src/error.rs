@@ -0,0 +1,91 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    io,
+    path::PathBuf,
+};
+
+#[derive(Debug)]
+pub enum ProblemError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        token: String,
+        message: String,
+    },
+    MissingField {
+        path: PathBuf,
+        line: usize,
+        field: &'static str,
+    },
+    /// `path` is that of the config file, if it was read from one.
+    Config {
+        path: Option<PathBuf>,
+        message: String,
+    },
+    /// The instance file changed since a solution was found on it.
+    InstanceMismatch {
+        path: PathBuf,
+    },
+    UnknownProblem(String),
+}
+
+impl Display for ProblemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProblemError::Io { path, source } => {
+                write!(f, "{}: {}", path.display(), source)
+            }
+            ProblemError::Parse {
+                path,
+                line,
+                column,
+                token,
+                message,
+            } => write!(
+                f,
+                "{}:{}:{}: unable to parse `{}`: {}",
+                path.display(),
+                line,
+                column,
+                token,
+                message
+            ),
+            ProblemError::MissingField { path, line, field } => {
+                write!(f, "{}:{}: missing {}", path.display(), line, field)
+            }
+            ProblemError::Config {
+                path: Some(path),
+                message,
+            } => {
+                write!(f, "{}: invalid config: {}", path.display(), message)
+            }
+            ProblemError::Config {
+                path: None,
+                message,
+            } => write!(f, "invalid config: {message}"),
+            ProblemError::InstanceMismatch { path } => write!(
+                f,
+                "{}: differs from the instance the solution was found on",
+                path.display()
+            ),
+            ProblemError::UnknownProblem(problem) => {
+                write!(f, "Problem not found: {problem}")
+            }
+        }
+    }
+}
+
+impl Error for ProblemError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ProblemError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
@@ -1,58 +1,210 @@
-use std::path::Path;
+use std::{fs, path::Path};
 
 pub mod algebraic_function;
+mod dimacs;
+pub mod error;
 pub mod nqueens;
 pub mod nqueens_weighted;
 pub mod problem;
 pub mod radio;
+mod reader;
 pub mod sat_3;
+pub mod tsp;
+pub mod weighted_max_sat;
 
 use algebraic_function::AlgebraicFunction;
-use loader_config::Config;
+use error::ProblemError;
+use loader_config::{Config, InitStrategy, PopType};
 use nqueens::NQueens;
 use nqueens_weighted::NQueensWeighted;
+use population::Solution;
 use problem::Problem;
 use radio::Radio;
 use sat_3::SAT3;
+use sha2::{Digest, Sha256};
+use tsp::Tsp;
+use weighted_max_sat::WeightedMaxSat;
 
-/// # Panics
-/// Panics if the configuration file is not found
-/// or the problem is not implemented
+/// # Errors
+/// If the configuration or instance file cannot be loaded, the
+/// configuration does not suit the problem or the problem is not
+/// implemented
 pub fn problem_factory<P>(
     problem: &str,
     instance: &str,
     config_path: P,
-) -> (Box<dyn Problem + Send + Sync>, Config)
+) -> Result<(Box<dyn Problem + Send + Sync>, Config), ProblemError>
 where
     P: AsRef<Path>,
 {
-    let config = Config::new(config_path).expect("Config not found");
-    match problem.to_uppercase().as_str() {
-        "SAT-3" => {
-            let problem =
-                sat_3::load_instance(instance).expect("Instance not found");
-            (Box::new(SAT3::new(&problem, config)), config)
-        }
-        "RADIO" => {
-            let problem =
-                radio::load_instance(instance).expect("Instance not found");
-            (Box::new(Radio::new(problem, config)), config)
+    let config_path = config_path.as_ref();
+    let config =
+        Config::new(config_path).map_err(|error| ProblemError::Config {
+            path: Some(config_path.to_path_buf()),
+            message: error.to_string(),
+        })?;
+    let problem = build_problem(problem, instance)?;
+    check_config(&*problem, &config, Some(config_path))?;
+    Ok((problem, config))
+}
+
+/// Checks that `config` is valid and suits `problem`: the problem must
+/// decode the population type, whose dimension and bounds must fit those
+/// the problem sets, if any, and
+/// the solutions or heuristic seeding the population must suit it.
+///
+/// # Errors
+/// `ProblemError::Io` if a seeding solution cannot be loaded, and
+/// `ProblemError::Config`, naming `config_path` if given, if the config
+/// does not suit the problem
+pub fn check_config(
+    problem: &(dyn Problem + Send + Sync),
+    config: &Config,
+    config_path: Option<&Path>,
+) -> Result<(), ProblemError> {
+    let invalid = |message: String| ProblemError::Config {
+        path: config_path.map(Path::to_path_buf),
+        message,
+    };
+    config
+        .validate()
+        .map_err(|error| invalid(error.to_string()))?;
+    let pop_config = &config.pop_config;
+    if !problem.supports(pop_config.pop_type) {
+        return Err(invalid(format!(
+            "{} does not decode {:?} populations",
+            problem.get_name(),
+            pop_config.pop_type
+        )));
+    }
+    if let Some(dimension) = problem.dimension(pop_config.pop_type) {
+        if pop_config.dim != dimension {
+            return Err(invalid(format!(
+                "dim {} does not match the {} genes of {}",
+                pop_config.dim,
+                dimension,
+                problem.get_name()
+            )));
         }
-        "ALGEBRAIC-FUNCTION" => {
-            let problem = algebraic_function::load_instance(instance)
-                .expect("Instance not found");
-            (Box::new(AlgebraicFunction::new(problem, config)), config)
+    }
+    if let (PopType::Real | PopType::Integer, Some(bounds), Some(limits)) =
+        (pop_config.pop_type, pop_config.bounds, problem.bounds())
+    {
+        if bounds.lower < limits.lower || limits.upper < bounds.upper {
+            return Err(invalid(format!(
+                "bounds [{}, {}] exceed the bounds [{}, {}] of {}",
+                bounds.lower,
+                bounds.upper,
+                limits.lower,
+                limits.upper,
+                problem.get_name()
+            )));
         }
-        "NQUEENS" => {
-            let problem =
-                nqueens::load_instance(instance).expect("Instance not found");
-            (Box::new(NQueens::new(problem, config)), config)
+    }
+    match pop_config
+        .initialization
+        .as_ref()
+        .map(|init| &init.strategy)
+    {
+        Some(InitStrategy::Solutions(paths)) => {
+            for path in paths {
+                let solution = Solution::load(path).map_err(|source| {
+                    ProblemError::Io {
+                        path: path.into(),
+                        source,
+                    }
+                })?;
+                if !solution.chromosome.fits(pop_config) {
+                    return Err(invalid(format!(
+                        "solution {} is not a {:?} chromosome of {} genes",
+                        path, pop_config.pop_type, pop_config.dim
+                    )));
+                }
+            }
         }
-        "NQUEENS-WEIGHTED" => {
-            let problem = nqueens_weighted::load_instance(instance)
-                .expect("Instance not found");
-            (Box::new(NQueensWeighted::new(problem, config)), config)
+        Some(InitStrategy::Heuristic)
+            if problem.heuristic(&mut rand::thread_rng()).is_none() =>
+        {
+            return Err(invalid(format!(
+                "{} has no heuristic to seed the population",
+                problem.get_name()
+            )));
         }
-        _ => panic!("Problem not found"),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Builds `problem` on `instance`.
+///
+/// # Errors
+/// If the instance file cannot be loaded or the problem is not implemented
+pub fn build_problem(
+    problem: &str,
+    instance: &str,
+) -> Result<Box<dyn Problem + Send + Sync>, ProblemError> {
+    let problem: Box<dyn Problem + Send + Sync> =
+        match problem.to_uppercase().as_str() {
+            "SAT-3" => {
+                let problem = sat_3::load_instance(instance)?;
+                Box::new(SAT3::new(&problem))
+            }
+            "RADIO" => {
+                let problem = radio::load_instance(instance)?;
+                Box::new(Radio::new(problem))
+            }
+            "ALGEBRAIC-FUNCTION" => {
+                let problem = algebraic_function::load_instance(instance)?;
+                Box::new(AlgebraicFunction::new(problem))
+            }
+            "NQUEENS" => {
+                let problem = nqueens::load_instance(instance)?;
+                Box::new(NQueens::new(problem))
+            }
+            "NQUEENS-WEIGHTED" => {
+                let problem = nqueens_weighted::load_instance(instance)?;
+                Box::new(NQueensWeighted::new(problem))
+            }
+            "TSP" => {
+                let problem = tsp::load_instance(instance)?;
+                Box::new(Tsp::new(problem))
+            }
+            "WEIGHTED-MAX-SAT" => {
+                let problem = weighted_max_sat::load_instance(instance)?;
+                Box::new(WeightedMaxSat::new(&problem))
+            }
+            _ => return Err(ProblemError::UnknownProblem(problem.to_string())),
+        };
+    Ok(problem)
+}
+
+/// SHA-256 of the instance file, in hex.
+///
+/// # Errors
+/// If the instance file cannot be read
+pub fn instance_hash<P: AsRef<Path>>(
+    instance: P,
+) -> Result<String, ProblemError> {
+    let path = instance.as_ref();
+    let contents = fs::read(path).map_err(|source| ProblemError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    Ok(format!("{:x}", Sha256::digest(contents)))
+}
+
+/// Builds the problem `solution` was found on, to evaluate it again.
+///
+/// # Errors
+/// If the instance file cannot be loaded or changed since the solution was
+/// found, or the problem is not implemented
+pub fn solution_problem(
+    solution: &Solution,
+) -> Result<Box<dyn Problem + Send + Sync>, ProblemError> {
+    if instance_hash(&solution.instance)? != solution.instance_hash {
+        return Err(ProblemError::InstanceMismatch {
+            path: solution.instance.clone().into(),
+        });
     }
+    build_problem(&solution.problem, &solution.instance)
 }
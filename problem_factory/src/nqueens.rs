@@ -1,13 +1,14 @@
-use std::{
-    fs::File,
-    io::{self, BufRead},
-    path::Path,
-};
+use std::path::Path;
 
-use loader_config::Config;
+use loader_config::PopType;
 use population::Individual;
+use rand::{seq::SliceRandom, RngCore};
 
-use crate::Problem;
+use crate::{
+    error::ProblemError,
+    reader::{field, read_rows},
+    Problem,
+};
 
 // #[cfg(not(feature = "sequential"))]
 // use rayon::prelude::*;
@@ -17,20 +18,21 @@ pub struct ProblemNQueens {
 }
 
 pub struct NQueens {
-    config: Config,
     problem: ProblemNQueens,
 }
 
 impl NQueens {
-    pub fn new(problem: ProblemNQueens, config: Config) -> Self {
-        NQueens { problem, config }
+    pub fn new(problem: ProblemNQueens) -> Self {
+        NQueens { problem }
     }
 }
 
 impl Problem for NQueens {
     fn decode(&self, individual: &Individual) -> Vec<f64> {
         match &individual {
-            Individual::Binary(_) => {
+            Individual::Binary(_)
+            | Individual::Real(_)
+            | Individual::Integer(_) => {
                 todo!()
             }
             Individual::Permuted(value) => value.iter().map(|&v| v as f64),
@@ -38,25 +40,17 @@ impl Problem for NQueens {
         .collect()
     }
 
-    fn get_config(&self) -> &Config {
-        &self.config
-    }
-
     fn normed_objective(&self, individual: &[f64]) -> f64 {
         1.0 - (self.objective(individual)
             / (self.problem.board_size - 1) as f64)
     }
 
-    fn constraint(&self, _: &[f64]) -> f64 {
-        0.0
+    fn supports(&self, pop_type: PopType) -> bool {
+        matches!(pop_type, PopType::Permuted)
     }
 
-    fn fitness(&self, individual: &Individual) -> f64 {
-        let config = self.get_config();
-        let decoded_individual = self.decode(individual);
-        let obj = self.normed_objective(&decoded_individual);
-        let constraint = self.constraint(&decoded_individual);
-        obj + config.constraint_penalty * constraint
+    fn dimension(&self, _pop_type: PopType) -> Option<usize> {
+        Some(self.problem.board_size)
     }
 
     fn objective(&self, individual: &[f64]) -> f64 {
@@ -92,30 +86,42 @@ impl Problem for NQueens {
     fn get_name(&self) -> String {
         String::from("N-QUEENS")
     }
-}
 
-pub fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+    /// Places a queen per line on a random free column whose diagonals are
+    /// free too, or on any free column when none is.
+    fn heuristic(&self, rng: &mut dyn RngCore) -> Option<Individual> {
+        let size = self.problem.board_size;
+        let mut free: Vec<usize> = (0..size).collect();
+        let mut diagonals = vec![false; 2 * size];
+        let mut anti_diagonals = vec![false; 2 * size];
+        let mut queens = Vec::with_capacity(size);
+        for line in 0..size {
+            free.shuffle(rng);
+            let position = free
+                .iter()
+                .position(|&column| {
+                    !diagonals[line + column]
+                        && !anti_diagonals[line + size - column]
+                })
+                .unwrap_or(0);
+            let column = free.swap_remove(position);
+            diagonals[line + column] = true;
+            anti_diagonals[line + size - column] = true;
+            queens.push(column);
+        }
+        Some(Individual::Permuted(queens))
+    }
 }
 
-pub fn load_instance<P>(filename: P) -> io::Result<ProblemNQueens>
+/// # Errors
+/// If the file cannot be read or does not start with the board size
+pub fn load_instance<P>(filename: P) -> Result<ProblemNQueens, ProblemError>
 where
     P: AsRef<Path>,
 {
-    let problem = read_lines(filename)?
-        .map(|line| {
-            let line = line.expect("Unable to read line");
-            return line
-                .split_whitespace()
-                .map(|i| i.parse().expect("Unable to parse number"))
-                .collect();
-        })
-        .collect::<Vec<Vec<f64>>>();
+    let filename = filename.as_ref();
+    let problem = read_rows::<f64, _>(filename)?;
     Ok(ProblemNQueens {
-        board_size: problem[0][0] as usize,
+        board_size: field(&problem, filename, 0, 0, "board size")? as usize,
     })
 }
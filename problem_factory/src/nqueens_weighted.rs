@@ -1,13 +1,13 @@
-use std::{
-    fs::File,
-    io::{self, BufRead},
-    path::Path,
-};
+use std::path::Path;
 
-use loader_config::Config;
+use loader_config::PopType;
 use population::Individual;
 
-use crate::Problem;
+use crate::{
+    error::ProblemError,
+    reader::{field, read_rows},
+    Problem,
+};
 
 // #[cfg(not(feature = "sequential"))]
 // use rayon::prelude::*;
@@ -19,20 +19,21 @@ pub struct ProblemNQueensWeighted {
 }
 
 pub struct NQueensWeighted {
-    config: Config,
     problem: ProblemNQueensWeighted,
 }
 
 impl NQueensWeighted {
-    pub fn new(problem: ProblemNQueensWeighted, config: Config) -> Self {
-        NQueensWeighted { problem, config }
+    pub fn new(problem: ProblemNQueensWeighted) -> Self {
+        NQueensWeighted { problem }
     }
 }
 
 impl Problem for NQueensWeighted {
     fn decode(&self, individual: &Individual) -> Vec<f64> {
         match &individual {
-            Individual::Binary(_) => {
+            Individual::Binary(_)
+            | Individual::Real(_)
+            | Individual::Integer(_) => {
                 todo!()
             }
             Individual::Permuted(value) => value.iter().map(|&v| v as f64),
@@ -40,14 +41,18 @@ impl Problem for NQueensWeighted {
         .collect()
     }
 
-    fn get_config(&self) -> &Config {
-        &self.config
-    }
-
     fn normed_objective(&self, individual: &[f64]) -> f64 {
         self.objective(individual) / self.problem.max_fo
     }
 
+    fn supports(&self, pop_type: PopType) -> bool {
+        matches!(pop_type, PopType::Permuted)
+    }
+
+    fn dimension(&self, _pop_type: PopType) -> Option<usize> {
+        Some(self.problem.board_size)
+    }
+
     fn constraint(&self, individual: &[f64]) -> f64 {
         let collisions: usize = individual[0..individual.len() - 1]
             .iter()
@@ -78,14 +83,6 @@ impl Problem for NQueensWeighted {
         (collisions as f64) / self.problem.max_h
     }
 
-    fn fitness(&self, individual: &Individual) -> f64 {
-        let config = self.get_config();
-        let decoded_individual = self.decode(individual);
-        let obj = self.normed_objective(&decoded_individual);
-        let constraint = self.constraint(&decoded_individual);
-        obj + config.constraint_penalty * constraint
-    }
-
     fn objective(&self, individual: &[f64]) -> f64 {
         let board_size: f64 = self.problem.board_size as f64;
         let roots = individual
@@ -109,28 +106,17 @@ impl Problem for NQueensWeighted {
     }
 }
 
-pub fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
-pub fn load_instance<P>(filename: P) -> io::Result<ProblemNQueensWeighted>
+/// # Errors
+/// If the file cannot be read or does not start with the board size
+pub fn load_instance<P>(
+    filename: P,
+) -> Result<ProblemNQueensWeighted, ProblemError>
 where
     P: AsRef<Path>,
 {
-    let problem = read_lines(filename)?
-        .map(|line| {
-            let line = line.expect("Unable to read line");
-            return line
-                .split_whitespace()
-                .map(|i| i.parse().expect("Unable to parse number"))
-                .collect();
-        })
-        .collect::<Vec<Vec<f64>>>();
-    let board_size = problem[0][0];
+    let filename = filename.as_ref();
+    let problem = read_rows::<f64, _>(filename)?;
+    let board_size = field(&problem, filename, 0, 0, "board size")?;
     let max_fo = (0..board_size as usize)
         .map(|i| {
             let fi = i as f64;
@@ -1,12 +1,53 @@
-use loader_config::Config;
+use loader_config::{BoundConfig, PopType};
 use population::Individual;
+use rand::RngCore;
 
+/// Problem the GA maximizes the fitness of. Only the name, the decoding and
+/// the objective are required, the other methods have defaults.
 pub trait Problem {
     fn get_name(&self) -> String;
-    fn get_config(&self) -> &Config;
-    fn objective(&self, individual: &[f64]) -> f64;
-    fn normed_objective(&self, individual: &[f64]) -> f64;
-    fn constraint(&self, individual: &[f64]) -> f64;
-    fn fitness(&self, individual: &Individual) -> f64;
     fn decode(&self, individual: &Individual) -> Vec<f64>;
+    fn objective(&self, individual: &[f64]) -> f64;
+    /// Objective scaled for the fitness, the objective itself by default.
+    fn normed_objective(&self, individual: &[f64]) -> f64 {
+        self.objective(individual)
+    }
+    /// Violation of the constraints, 0 when they hold. Unconstrained by
+    /// default.
+    fn constraint(&self, _individual: &[f64]) -> f64 {
+        0.0
+    }
+    /// Whether the constraints hold, that is the violation is not positive
+    /// by default.
+    fn is_feasible(&self, individual: &[f64]) -> bool {
+        self.constraint(individual) <= 0.0
+    }
+    /// Whether [`Problem::decode`] handles individuals encoded as
+    /// `pop_type`, any encoding by default.
+    fn supports(&self, _pop_type: PopType) -> bool {
+        true
+    }
+    /// Genes of an individual encoded as `pop_type`, which the config's
+    /// `dim` must match. `None` when any number of genes decodes.
+    fn dimension(&self, _pop_type: PopType) -> Option<usize> {
+        None
+    }
+    /// Range of the genes of real and integer individuals, which the
+    /// config's bounds must lie within. `None` when unbounded.
+    fn bounds(&self) -> Option<BoundConfig> {
+        None
+    }
+    /// Best known objective of the instance, logged as the optimality gap.
+    fn known_optimum(&self) -> Option<f64> {
+        None
+    }
+    /// Individual built by a constructive heuristic, randomized by `rng` so
+    /// successive calls differ. `None` for problems without one.
+    fn heuristic(&self, _rng: &mut dyn RngCore) -> Option<Individual> {
+        None
+    }
+    /// Makes `individual` feasible, or less infeasible, leaving feasible
+    /// individuals unchanged. Applied before evaluation when the config
+    /// enables repair, does nothing by default.
+    fn repair(&self, _individual: &mut Individual) {}
 }
@@ -1,13 +1,16 @@
-use std::{
-    fs::File,
-    io::{self, BufRead},
-    path::Path,
-};
+use std::path::Path;
 
-use loader_config::Config;
+use loader_config::{BoundConfig, PopType};
 use population::Individual;
 
-use crate::Problem;
+use crate::{
+    error::ProblemError,
+    reader::{field, read_rows},
+    Problem,
+};
+
+/// Bits encoding the radios of each line in binary individuals.
+const LINE_BITS: usize = 5;
 
 pub struct ProblemRadio {
     max_h: f64,
@@ -18,13 +21,12 @@ pub struct ProblemRadio {
 }
 
 pub struct Radio {
-    config: Config,
     problem: ProblemRadio,
 }
 
 impl Radio {
-    pub fn new(problem: ProblemRadio, config: Config) -> Self {
-        Radio { config, problem }
+    pub fn new(problem: ProblemRadio) -> Self {
+        Radio { problem }
     }
 }
 
@@ -34,15 +36,18 @@ impl Problem for Radio {
             Individual::Binary(value) => {
                 value.iter().map(|&v| f64::from(u32::from(v)))
             }
-            Individual::Permuted(_) => todo!(),
+            Individual::Integer(value) => {
+                return value.iter().map(|&v| v as f64).collect()
+            }
+            Individual::Permuted(_) | Individual::Real(_) => todo!(),
         }
         .collect();
-        let qtd_line_a: f64 = values[0..5]
+        let qtd_line_a: f64 = values[0..LINE_BITS]
             .to_owned()
             .clone()
             .iter()
             .fold(0.0, |a, &b| 2.0 * a + b);
-        let qtd_line_b: f64 = values[5..]
+        let qtd_line_b: f64 = values[LINE_BITS..]
             .to_owned()
             .clone()
             .iter()
@@ -50,14 +55,30 @@ impl Problem for Radio {
         vec![qtd_line_a, qtd_line_b]
     }
 
-    fn get_config(&self) -> &Config {
-        &self.config
-    }
-
     fn normed_objective(&self, individual: &[f64]) -> f64 {
         self.objective(individual) / self.problem.max_fo
     }
 
+    fn supports(&self, pop_type: PopType) -> bool {
+        matches!(pop_type, PopType::Binary | PopType::Integer)
+    }
+
+    fn dimension(&self, pop_type: PopType) -> Option<usize> {
+        match pop_type {
+            PopType::Binary => Some(2 * LINE_BITS),
+            PopType::Integer => Some(2),
+            PopType::Real | PopType::Permuted => None,
+        }
+    }
+
+    /// Radios of a line, from none up to one per employee.
+    fn bounds(&self) -> Option<BoundConfig> {
+        Some(BoundConfig {
+            lower: 0.0,
+            upper: self.problem.qtd_employees as f64,
+        })
+    }
+
     fn constraint(&self, individual: &[f64]) -> f64 {
         let qtd_1 = individual[0];
         let qtd_2 = individual[1];
@@ -71,14 +92,6 @@ impl Problem for Radio {
         f64::max(0.0, p)
     }
 
-    fn fitness(&self, individual: &Individual) -> f64 {
-        let config = self.get_config();
-        let decoded_individual = self.decode(individual);
-        let obj = self.normed_objective(&decoded_individual);
-        let constraint = self.constraint(&decoded_individual);
-        obj + config.constraint_penalty * constraint
-    }
-
     fn objective(&self, individual: &[f64]) -> f64 {
         let qtd_1 = individual[0];
         let qtd_2 = individual[1];
@@ -91,32 +104,19 @@ impl Problem for Radio {
     }
 }
 
-pub fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
+/// # Errors
+/// If the file cannot be read or is missing any of the radio parameters
+pub fn load_instance<P>(filename: P) -> Result<ProblemRadio, ProblemError>
 where
     P: AsRef<Path>,
 {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
-pub fn load_instance<P>(filename: P) -> io::Result<ProblemRadio>
-where
-    P: AsRef<Path>,
-{
-    let problem = read_lines(filename)?
-        .map(|line| {
-            let line = line.expect("Unable to read line");
-            return line
-                .split_whitespace()
-                .map(|i| i.parse().expect("Unable to parse number"))
-                .collect();
-        })
-        .collect::<Vec<Vec<f64>>>();
+    let filename = filename.as_ref();
+    let problem = read_rows::<f64, _>(filename)?;
     Ok(ProblemRadio {
-        profit_a: problem[0][0],
-        profit_b: problem[0][1],
-        qtd_employees: problem[1][0] as usize,
-        max_fo: problem[2][0],
-        max_h: problem[3][0],
+        profit_a: field(&problem, filename, 0, 0, "profit of line A")?,
+        profit_b: field(&problem, filename, 0, 1, "profit of line B")?,
+        qtd_employees: field(&problem, filename, 1, 0, "employees")? as usize,
+        max_fo: field(&problem, filename, 2, 0, "max objective")?,
+        max_h: field(&problem, filename, 3, 0, "max work hours")?,
     })
 }
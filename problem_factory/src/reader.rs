@@ -0,0 +1,119 @@
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{self, BufRead},
+    path::Path,
+    str::FromStr,
+};
+
+use crate::error::ProblemError;
+
+pub(crate) fn read_lines<P>(
+    filename: P,
+) -> io::Result<io::Lines<io::BufReader<File>>>
+where
+    P: AsRef<Path>,
+{
+    let file = File::open(filename)?;
+    Ok(io::BufReader::new(file).lines())
+}
+
+/// Reads an instance file as rows of whitespace separated values, keeping
+/// the line and column of every token for error reporting.
+pub(crate) fn read_rows<T, P>(filename: P) -> Result<Vec<Vec<T>>, ProblemError>
+where
+    T: FromStr,
+    T::Err: Display,
+    P: AsRef<Path>,
+{
+    let path = filename.as_ref();
+    let io_error = |source| ProblemError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    read_lines(path)
+        .map_err(io_error)?
+        .enumerate()
+        .map(|(line_index, line)| {
+            let line = line.map_err(io_error)?;
+            tokens(&line)
+                .map(|token| parse_token(path, line_index + 1, token))
+                .collect()
+        })
+        .collect()
+}
+
+/// Parses a `(column, token)` pair yielded by [`tokens`].
+pub(crate) fn parse_token<T>(
+    path: &Path,
+    line: usize,
+    (column, token): (usize, &str),
+) -> Result<T, ProblemError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    token.parse().map_err(|error: T::Err| {
+        parse_error(path, line, column, token, error.to_string())
+    })
+}
+
+pub(crate) fn parse_error(
+    path: &Path,
+    line: usize,
+    column: usize,
+    token: &str,
+    message: String,
+) -> ProblemError {
+    ProblemError::Parse {
+        path: path.to_path_buf(),
+        line,
+        column,
+        token: token.to_string(),
+        message,
+    }
+}
+
+/// Value at `row`/`column` of an instance read by [`read_rows`].
+pub(crate) fn field<T: Copy, P: AsRef<Path>>(
+    rows: &[Vec<T>],
+    filename: P,
+    row: usize,
+    column: usize,
+    name: &'static str,
+) -> Result<T, ProblemError> {
+    rows.get(row)
+        .and_then(|values| values.get(column))
+        .copied()
+        .ok_or_else(|| ProblemError::MissingField {
+            path: filename.as_ref().to_path_buf(),
+            line: row + 1,
+            field: name,
+        })
+}
+
+/// Whitespace separated tokens of a line with their 1-based column.
+pub(crate) fn tokens(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    line.split_whitespace().map(move |token| {
+        let offset = token.as_ptr() as usize - line.as_ptr() as usize;
+        (line[..offset].chars().count() + 1, token)
+    })
+}
+
+/// Writes `content` to `name` in a directory of its own, so that tests
+/// running in parallel never share a file.
+#[cfg(test)]
+pub(crate) fn test_instance(name: &str, content: &str) -> std::path::PathBuf {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static DIRECTORIES: AtomicUsize = AtomicUsize::new(0);
+    let directory = std::env::temp_dir().join(format!(
+        "problem_factory-{}-{}",
+        std::process::id(),
+        DIRECTORIES.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::create_dir_all(&directory).unwrap();
+    let path = directory.join(name);
+    std::fs::write(&path, content).unwrap();
+    path
+}
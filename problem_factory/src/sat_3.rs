@@ -1,27 +1,32 @@
-use std::{
-    fs::File,
-    io::{self, BufRead},
-    path::Path,
-};
+use std::{cmp::Ordering, path::Path};
 
-use loader_config::Config;
+use loader_config::PopType;
 use population::Individual;
+use rand::{seq::SliceRandom, Rng, RngCore};
 
-use crate::Problem;
+use crate::{
+    dimacs::{check_clause_count, header_values, literals, read_dimacs},
+    error::ProblemError,
+    Problem,
+};
+
+/// CNF formula read from a DIMACS file, literals as signed 1-based
+/// variable indexes.
+pub struct Cnf {
+    pub variables: usize,
+    pub clauses: Vec<Vec<i32>>,
+}
 
 pub struct SAT3 {
-    config: Config,
-    clause_id: Vec<(u32, u32, u32)>,
-    clause_neg: Vec<(bool, bool, bool)>,
+    variables: usize,
+    clauses: Vec<Vec<(usize, bool)>>,
 }
 
 impl SAT3 {
-    pub fn new(problem: &[(i32, i32, i32)], config: Config) -> SAT3 {
-        let (clause_id, clause_neg) = SAT3::clauses(problem);
+    pub fn new(problem: &Cnf) -> SAT3 {
         SAT3 {
-            config,
-            clause_id,
-            clause_neg,
+            variables: problem.variables,
+            clauses: problem.clauses.iter().map(|c| SAT3::clause(c)).collect(),
         }
     }
 }
@@ -32,42 +37,34 @@ impl Problem for SAT3 {
             Individual::Binary(value) => {
                 value.iter().map(|&v| f64::from(u32::from(v)))
             }
-            Individual::Permuted(_) => todo!(),
+            Individual::Permuted(_)
+            | Individual::Real(_)
+            | Individual::Integer(_) => todo!(),
         }
         .collect()
     }
 
-    fn get_config(&self) -> &Config {
-        &self.config
-    }
-
     fn normed_objective(&self, individual: &[f64]) -> f64 {
-        self.objective(individual) / 430.0
+        if self.clauses.is_empty() {
+            return 1.0;
+        }
+        self.objective(individual) / self.clauses.len() as f64
     }
 
-    fn constraint(&self, _: &[f64]) -> f64 {
-        0.0
+    fn supports(&self, pop_type: PopType) -> bool {
+        matches!(pop_type, PopType::Binary)
     }
 
-    fn fitness(&self, individual: &Individual) -> f64 {
-        let config = self.get_config();
-        let decoded_individual = self.decode(individual);
-        let obj = self.normed_objective(&decoded_individual);
-        let constraint = self.constraint(&decoded_individual);
-        obj + config.constraint_penalty * constraint
+    fn dimension(&self, _pop_type: PopType) -> Option<usize> {
+        Some(self.variables)
     }
 
     fn objective(&self, individual: &[f64]) -> f64 {
-        let clauses_neg_iter = self.clause_neg.iter();
-        let clauses_satisfied_iter = self.clause_id.iter();
-
-        clauses_satisfied_iter
-            .zip(clauses_neg_iter)
-            .map(|i| {
-                let (clause, clause_neg) = i;
-                let solution = individual;
+        self.clauses
+            .iter()
+            .map(|clause| {
                 let evaluated_solution =
-                    SAT3::eval_solution(solution, clause, *clause_neg);
+                    SAT3::eval_solution(individual, clause);
                 f64::from(u32::from(evaluated_solution))
             })
             .sum::<f64>()
@@ -76,81 +73,190 @@ impl Problem for SAT3 {
     fn get_name(&self) -> String {
         String::from("SAT-3")
     }
+
+    /// Assigns the variables in random order, each to the value satisfying
+    /// more of the clauses left unsatisfied, ties broken at random.
+    fn heuristic(&self, rng: &mut dyn RngCore) -> Option<Individual> {
+        let variables = self.variables;
+        let mut occurrences: Vec<Vec<(usize, bool)>> =
+            vec![Vec::new(); variables];
+        for (index, clause) in self.clauses.iter().enumerate() {
+            for &(variable, negated) in clause {
+                occurrences[variable].push((index, negated));
+            }
+        }
+        let mut satisfied = vec![false; self.clauses.len()];
+        let mut assignment = vec![false; variables];
+        let mut order: Vec<usize> = (0..variables).collect();
+        order.shuffle(rng);
+        for variable in order {
+            let (positive, negative) = occurrences[variable]
+                .iter()
+                .filter(|&&(clause, _)| !satisfied[clause])
+                .fold((0, 0), |(positive, negative), &(_, negated)| {
+                    if negated {
+                        (positive, negative + 1)
+                    } else {
+                        (positive + 1, negative)
+                    }
+                });
+            let value = match positive.cmp(&negative) {
+                Ordering::Greater => true,
+                Ordering::Less => false,
+                Ordering::Equal => rng.gen(),
+            };
+            assignment[variable] = value;
+            for &(clause, negated) in &occurrences[variable] {
+                if value != negated {
+                    satisfied[clause] = true;
+                }
+            }
+        }
+        Some(Individual::Binary(assignment))
+    }
 }
-type ClausesType = (Vec<(u32, u32, u32)>, Vec<(bool, bool, bool)>);
 impl SAT3 {
-    fn clauses(problem: &[(i32, i32, i32)]) -> ClausesType {
-        let clause_id = problem
+    /// Clause as 0-based variable indexes with their negation flags.
+    pub(crate) fn clause(literals: &[i32]) -> Vec<(usize, bool)> {
+        literals
             .iter()
-            .map(|(a, b, c)| {
-                (
-                    (a.abs() - 1) as u32,
-                    (b.abs() - 1) as u32,
-                    (c.abs() - 1) as u32,
-                )
+            .map(|literal| {
+                ((literal.unsigned_abs() - 1) as usize, *literal < 0)
             })
-            .collect();
-        let clause_neg = problem
-            .iter()
-            .map(|(a, b, c)| (*a < 0, *b < 0, *c < 0))
-            .collect();
-        (clause_id, clause_neg)
+            .collect()
     }
 
-    fn eval_solution(
+    pub(crate) fn eval_solution(
         solution: &[f64],
-        clause_id: &(u32, u32, u32),
-        clause_neg: (bool, bool, bool),
+        clause: &[(usize, bool)],
     ) -> bool {
-        let (a, b, c) = clause_id;
-        let (na, nb, nc) = clause_neg;
-
-        let solution_a: bool =
-            (solution[*a as usize] - 1.0_f64).abs() < f64::EPSILON;
-        let solution_b: bool =
-            (solution[*b as usize] - 1.0_f64).abs() < f64::EPSILON;
-        let solution_c: bool =
-            (solution[*c as usize] - 1.0_f64).abs() < f64::EPSILON;
-        let checked_solution_a = (!solution_a && na) || (solution_a && !na);
-        let checked_solution_b = (!solution_b && nb) || (solution_b && !nb);
-        let checked_solution_c = (!solution_c && nc) || (solution_c && !nc);
-        checked_solution_a || checked_solution_b || checked_solution_c
+        clause.iter().any(|&(variable, negated)| {
+            let value = (solution[variable] - 1.0_f64).abs() < f64::EPSILON;
+            value != negated
+        })
     }
 }
 
-pub fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
+/// Reads a DIMACS CNF file with an optional `p cnf <variables> <clauses>`
+/// header and clauses of any width. Without a header the variable count is
+/// the highest variable seen.
+///
+/// # Errors
+/// If the file cannot be read, a token is not a literal or the formula does
+/// not match its header
+pub fn load_instance<P>(filename: P) -> Result<Cnf, ProblemError>
 where
     P: AsRef<Path>,
 {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+    let path = filename.as_ref();
+    let dimacs = read_dimacs(path, "cnf")?;
+    let header = dimacs
+        .header
+        .map(|header| {
+            header_values(path, &header, "p cnf <variables> <clauses>", 2..=2)
+        })
+        .transpose()?;
+    let declared_variables = header.as_ref().map(|header| header[0].2 as usize);
+    let clauses = dimacs
+        .clauses
+        .iter()
+        .map(|(_, clause)| literals(path, clause, declared_variables))
+        .collect::<Result<Vec<_>, _>>()?;
+    if let Some(header) = &header {
+        check_clause_count(path, header[1], clauses.len())?;
+    }
+    let variables = declared_variables.unwrap_or_else(|| {
+        clauses
+            .iter()
+            .flatten()
+            .map(|literal| literal.unsigned_abs() as usize)
+            .max()
+            .unwrap_or(0)
+    });
+    Ok(Cnf { variables, clauses })
 }
 
-pub fn load_instance<P>(filename: P) -> io::Result<Vec<(i32, i32, i32)>>
-where
-    P: AsRef<Path>,
-{
-    let problem = read_lines(filename)?
-        .map(|line| {
-            let line = line.expect("Unable to read line");
-            let mut clause = line.split_whitespace();
-            let a: i32 = clause
-                .next()
-                .expect("Unable to read first number")
-                .parse()
-                .expect("Unable to convert first number to i32");
-            let b: i32 = clause
-                .next()
-                .expect("Unable to read second number")
-                .parse()
-                .expect("Unable to convert second number to i32");
-            let c: i32 = clause
-                .next()
-                .expect("Unable to read third number")
-                .parse()
-                .expect("Unable to convert third number to i32");
-            (a, b, c)
-        })
-        .collect::<Vec<(i32, i32, i32)>>();
-    Ok(problem)
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::reader::test_instance;
+
+    #[test]
+    fn reads_clauses_spanning_lines() {
+        let path = test_instance(
+            "spanning.cnf",
+            "c comment\np cnf 4 3\n1 -2\n3 0 -4 0\n2 3 4 0\n%\n0\n",
+        );
+        let cnf = load_instance(&path).unwrap();
+        assert_eq!(cnf.variables, 4);
+        assert_eq!(cnf.clauses, vec![vec![1, -2, 3], vec![-4], vec![2, 3, 4]]);
+    }
+
+    #[test]
+    fn counts_variables_without_header() {
+        let path = test_instance("headerless.cnf", "1 -5 0\n2 3 0\n");
+        let cnf = load_instance(&path).unwrap();
+        assert_eq!(cnf.variables, 5);
+        assert_eq!(cnf.clauses.len(), 2);
+    }
+
+    #[test]
+    fn rejects_clause_count_mismatch() {
+        let path = test_instance("count.cnf", "p cnf 3 3\n1 2 0\n-3 0\n");
+        let Err(error) = load_instance(&path) else {
+            panic!("clause count mismatch accepted");
+        };
+        assert!(matches!(
+            error,
+            ProblemError::Parse {
+                line: 1,
+                column: 9,
+                ..
+            }
+        ));
+        assert!(error
+            .to_string()
+            .contains("header declares 3 clauses, found 2"));
+    }
+
+    #[test]
+    fn rejects_undeclared_variable() {
+        let path = test_instance("undeclared.cnf", "p cnf 3 1\n1 -4 0\n");
+        assert!(matches!(
+            load_instance(&path),
+            Err(ProblemError::Parse {
+                line: 2,
+                column: 3,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_other_format() {
+        let path = test_instance("format.cnf", "p wcnf 3 1\n1 2 0\n");
+        assert!(matches!(
+            load_instance(&path),
+            Err(ProblemError::Parse { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn satisfies_formula_without_clauses() {
+        let path = test_instance("empty.cnf", "p cnf 3 0\n");
+        let sat = SAT3::new(&load_instance(&path).unwrap());
+        let decoded = sat.decode(&Individual::Binary(vec![false; 3]));
+        assert!((sat.normed_objective(&decoded) - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn loads_bundled_instance() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../data/instances/sat-3/uf100-01.cnf");
+        let cnf = load_instance(path).unwrap();
+        assert_eq!(cnf.variables, 100);
+        assert!(cnf.clauses.iter().all(|clause| clause.len() == 3));
+    }
 }
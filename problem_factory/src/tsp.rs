@@ -0,0 +1,537 @@
+use std::{collections::HashMap, f64::consts::PI, path::Path};
+
+use loader_config::PopType;
+use population::Individual;
+
+use crate::{
+    error::ProblemError,
+    reader::{parse_error, parse_token, read_lines, tokens},
+    Problem,
+};
+
+/// Earth radius used by TSPLIB `GEO` distances.
+const EARTH_RADIUS: f64 = 6378.388;
+
+pub struct ProblemTsp {
+    pub name: String,
+    pub dimension: usize,
+    /// Row-major `dimension` x `dimension` distance matrix.
+    distances: Vec<f64>,
+    /// Upper bound of any tour length: every city's longest edge.
+    max_length: f64,
+    /// Length of the `.opt.tour` next to the instance, when available.
+    pub optimal_length: Option<f64>,
+}
+
+impl ProblemTsp {
+    fn distance(&self, from: usize, to: usize) -> f64 {
+        self.distances[from * self.dimension + to]
+    }
+
+    fn tour_length(&self, tour: &[usize]) -> f64 {
+        tour.iter()
+            .zip(tour.iter().cycle().skip(1))
+            .map(|(&from, &to)| self.distance(from, to))
+            .sum()
+    }
+}
+
+pub struct Tsp {
+    problem: ProblemTsp,
+}
+
+impl Tsp {
+    pub fn new(problem: ProblemTsp) -> Self {
+        Tsp { problem }
+    }
+}
+
+impl Problem for Tsp {
+    fn decode(&self, individual: &Individual) -> Vec<f64> {
+        match &individual {
+            Individual::Binary(_)
+            | Individual::Real(_)
+            | Individual::Integer(_) => {
+                todo!()
+            }
+            Individual::Permuted(value) => value.iter().map(|&v| v as f64),
+        }
+        .collect()
+    }
+
+    fn normed_objective(&self, individual: &[f64]) -> f64 {
+        1.0 - (self.objective(individual) / self.problem.max_length)
+    }
+
+    fn supports(&self, pop_type: PopType) -> bool {
+        matches!(pop_type, PopType::Permuted)
+    }
+
+    fn dimension(&self, _pop_type: PopType) -> Option<usize> {
+        Some(self.problem.dimension)
+    }
+
+    /// Length of the closed tour visiting the cities in order.
+    fn objective(&self, individual: &[f64]) -> f64 {
+        let tour: Vec<usize> =
+            individual.iter().map(|&city| city as usize).collect();
+        self.problem.tour_length(&tour)
+    }
+
+    fn known_optimum(&self) -> Option<f64> {
+        self.problem.optimal_length
+    }
+
+    fn get_name(&self) -> String {
+        String::from("TSP")
+    }
+}
+
+enum Section {
+    Specification,
+    NodeCoord,
+    EdgeWeight,
+    Tour,
+    Ignored,
+}
+
+/// Specification values of a TSPLIB file with their line and column.
+type Specification = HashMap<String, (usize, usize, String)>;
+
+/// Content of a TSPLIB file, validated by [`load_instance`] and
+/// [`load_tour`].
+struct Tsplib {
+    specification: Specification,
+    coordinates: Vec<(f64, f64)>,
+    weights: Vec<f64>,
+    /// 0-based cities with the line and column they were read at.
+    tour: Vec<(usize, usize, usize)>,
+    lines: usize,
+}
+
+fn read_tsplib(path: &Path) -> Result<Tsplib, ProblemError> {
+    let io_error = |source| ProblemError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    let mut tsplib = Tsplib {
+        specification: HashMap::new(),
+        coordinates: Vec::new(),
+        weights: Vec::new(),
+        tour: Vec::new(),
+        lines: 0,
+    };
+    let mut section = Section::Specification;
+    for (line_index, line) in read_lines(path).map_err(io_error)?.enumerate() {
+        let line = line.map_err(io_error)?;
+        let line_number = line_index + 1;
+        tsplib.lines = line_number;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if trimmed == "EOF" {
+            break;
+        }
+        if trimmed.ends_with("_SECTION") {
+            section = match trimmed {
+                "NODE_COORD_SECTION" => Section::NodeCoord,
+                "EDGE_WEIGHT_SECTION" => Section::EdgeWeight,
+                "TOUR_SECTION" => Section::Tour,
+                _ => Section::Ignored,
+            };
+            continue;
+        }
+        match section {
+            Section::Specification => {
+                let Some((key, value)) = line.split_once(':') else {
+                    return Err(parse_error(
+                        path,
+                        line_number,
+                        1,
+                        trimmed,
+                        String::from("expected `KEY : VALUE`"),
+                    ));
+                };
+                let column = line.len() - value.trim_start().len() + 1;
+                tsplib.specification.insert(
+                    key.trim().to_string(),
+                    (line_number, column, value.trim().to_string()),
+                );
+            }
+            Section::NodeCoord => {
+                let fields: Vec<(usize, &str)> = tokens(&line).collect();
+                if fields.len() < 3 {
+                    return Err(ProblemError::MissingField {
+                        path: path.to_path_buf(),
+                        line: line_number,
+                        field: "node coordinates",
+                    });
+                }
+                let x = parse_token(path, line_number, fields[1])?;
+                let y = parse_token(path, line_number, fields[2])?;
+                tsplib.coordinates.push((x, y));
+            }
+            Section::EdgeWeight => {
+                for token in tokens(&line) {
+                    tsplib.weights.push(parse_token(path, line_number, token)?);
+                }
+            }
+            Section::Tour => {
+                for token in tokens(&line) {
+                    let city: i64 = parse_token(path, line_number, token)?;
+                    if city == -1 {
+                        section = Section::Ignored;
+                        break;
+                    }
+                    let city = usize::try_from(city)
+                        .ok()
+                        .filter(|&city| city > 0)
+                        .ok_or_else(|| {
+                            parse_error(
+                                path,
+                                line_number,
+                                token.0,
+                                token.1,
+                                String::from("cities are numbered from 1"),
+                            )
+                        })?;
+                    tsplib.tour.push((line_number, token.0, city - 1));
+                }
+            }
+            Section::Ignored => {}
+        }
+    }
+    Ok(tsplib)
+}
+
+fn specification<'a>(
+    path: &Path,
+    tsplib: &'a Tsplib,
+    key: &'static str,
+) -> Result<(usize, usize, &'a str), ProblemError> {
+    tsplib
+        .specification
+        .get(key)
+        .map(|(line, column, value)| (*line, *column, value.as_str()))
+        .ok_or_else(|| ProblemError::MissingField {
+            path: path.to_path_buf(),
+            line: tsplib.lines,
+            field: key,
+        })
+}
+
+fn dimension(path: &Path, tsplib: &Tsplib) -> Result<usize, ProblemError> {
+    let (line, column, value) = specification(path, tsplib, "DIMENSION")?;
+    let dimension = parse_token(path, line, (column, value))?;
+    if dimension < 2 {
+        return Err(parse_error(
+            path,
+            line,
+            column,
+            value,
+            String::from("dimension must be at least 2"),
+        ));
+    }
+    Ok(dimension)
+}
+
+/// `nint` of the TSPLIB specification.
+fn nint(value: f64) -> f64 {
+    (value + 0.5).floor()
+}
+
+/// Latitude or longitude in radians from TSPLIB `DDD.MM` degrees.
+fn geo_radians(value: f64) -> f64 {
+    let degrees = value.trunc();
+    let minutes = value - degrees;
+    // TSPLIB fixes pi to 3.141592 for GEO instances.
+    let pi = (PI * 1e6).trunc() / 1e6;
+    pi * (degrees + 5.0 * minutes / 3.0) / 180.0
+}
+
+fn coordinate_distance(
+    weight_type: &str,
+    (x_1, y_1): (f64, f64),
+    (x_2, y_2): (f64, f64),
+) -> f64 {
+    match weight_type {
+        "EUC_2D" => nint((x_1 - x_2).hypot(y_1 - y_2)),
+        "ATT" => {
+            let distance =
+                (((x_1 - x_2).powi(2) + (y_1 - y_2).powi(2)) / 10.0).sqrt();
+            let rounded = nint(distance);
+            if rounded < distance {
+                rounded + 1.0
+            } else {
+                rounded
+            }
+        }
+        "GEO" => {
+            let (latitude_1, longitude_1) =
+                (geo_radians(x_1), geo_radians(y_1));
+            let (latitude_2, longitude_2) =
+                (geo_radians(x_2), geo_radians(y_2));
+            let q1 = (longitude_1 - longitude_2).cos();
+            let q2 = (latitude_1 - latitude_2).cos();
+            let q3 = (latitude_1 + latitude_2).cos();
+            (EARTH_RADIUS * (0.5 * ((1.0 + q1) * q2 - (1.0 - q1) * q3)).acos()
+                + 1.0)
+                .trunc()
+        }
+        _ => unreachable!("Unsupported edge weight type {weight_type}"),
+    }
+}
+
+fn distances(
+    path: &Path,
+    tsplib: &Tsplib,
+    dimension: usize,
+) -> Result<Vec<f64>, ProblemError> {
+    let (line, column, weight_type) =
+        specification(path, tsplib, "EDGE_WEIGHT_TYPE")?;
+    match weight_type {
+        "EUC_2D" | "ATT" | "GEO" => {
+            if tsplib.coordinates.len() != dimension {
+                return Err(ProblemError::MissingField {
+                    path: path.to_path_buf(),
+                    line: tsplib.lines,
+                    field: "node coordinates",
+                });
+            }
+            Ok(tsplib
+                .coordinates
+                .iter()
+                .flat_map(|&from| {
+                    tsplib.coordinates.iter().map(move |&to| {
+                        coordinate_distance(weight_type, from, to)
+                    })
+                })
+                .collect())
+        }
+        "EXPLICIT" => {
+            let (format_line, format_column, format) =
+                specification(path, tsplib, "EDGE_WEIGHT_FORMAT")?;
+            if format != "FULL_MATRIX" {
+                return Err(parse_error(
+                    path,
+                    format_line,
+                    format_column,
+                    format,
+                    String::from("only FULL_MATRIX edge weights are supported"),
+                ));
+            }
+            if tsplib.weights.len() != dimension * dimension {
+                return Err(ProblemError::MissingField {
+                    path: path.to_path_buf(),
+                    line: tsplib.lines,
+                    field: "edge weights",
+                });
+            }
+            Ok(tsplib.weights.clone())
+        }
+        _ => Err(parse_error(
+            path,
+            line,
+            column,
+            weight_type,
+            String::from("unsupported edge weight type"),
+        )),
+    }
+}
+
+/// Reads a TSPLIB symmetric TSP instance with `EUC_2D`, `GEO`, `ATT` or
+/// `EXPLICIT` `FULL_MATRIX` edge weights. A `<name>.opt.tour` file next to
+/// it is read as the optimal tour.
+///
+/// # Errors
+/// If the instance or its optimal tour cannot be read or are malformed
+pub fn load_instance<P>(filename: P) -> Result<ProblemTsp, ProblemError>
+where
+    P: AsRef<Path>,
+{
+    let path = filename.as_ref();
+    let tsplib = read_tsplib(path)?;
+    let dimension = dimension(path, &tsplib)?;
+    let distances = distances(path, &tsplib, dimension)?;
+    let max_length = distances
+        .chunks(dimension)
+        .map(|row| row.iter().copied().fold(0.0, f64::max))
+        .sum();
+    let name = tsplib
+        .specification
+        .get("NAME")
+        .map_or_else(String::new, |(_, _, name)| name.clone());
+    let mut problem = ProblemTsp {
+        name,
+        dimension,
+        distances,
+        max_length,
+        optimal_length: None,
+    };
+
+    let tour_path = path.with_extension("opt.tour");
+    if tour_path.is_file() {
+        let tour = load_tour(&tour_path, dimension)?;
+        problem.optimal_length = Some(problem.tour_length(&tour));
+    }
+    Ok(problem)
+}
+
+/// Reads a TSPLIB tour as 0-based cities.
+///
+/// # Errors
+/// If the file cannot be read or is not a tour over `dimension` cities
+pub fn load_tour<P>(
+    filename: P,
+    dimension: usize,
+) -> Result<Vec<usize>, ProblemError>
+where
+    P: AsRef<Path>,
+{
+    let path = filename.as_ref();
+    let tsplib = read_tsplib(path)?;
+    let mut visited = vec![false; dimension];
+    for &(line, column, city) in &tsplib.tour {
+        let message = if city >= dimension {
+            format!("city above the instance's {dimension}")
+        } else if visited[city] {
+            String::from("city visited twice")
+        } else {
+            visited[city] = true;
+            continue;
+        };
+        return Err(parse_error(
+            path,
+            line,
+            column,
+            &(city + 1).to_string(),
+            message,
+        ));
+    }
+    if tsplib.tour.len() != dimension {
+        return Err(parse_error(
+            path,
+            tsplib.lines,
+            1,
+            "EOF",
+            format!(
+                "tour visits {} of the {dimension} cities",
+                tsplib.tour.len()
+            ),
+        ));
+    }
+    Ok(tsplib.tour.into_iter().map(|(_, _, city)| city).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::reader::test_instance;
+
+    fn instance(name: &str, weight_type: &str, nodes: &str) -> PathBuf {
+        let dimension = nodes.lines().count();
+        test_instance(
+            &format!("{name}.tsp"),
+            &format!(
+                "NAME : {name}\nTYPE : TSP\nDIMENSION : \
+                 {dimension}\nEDGE_WEIGHT_TYPE : \
+                 {weight_type}\nNODE_COORD_SECTION\n{nodes}EOF\n"
+            ),
+        )
+    }
+
+    #[test]
+    fn rounds_euclidean_distances_to_nearest() {
+        let path = instance("euc", "EUC_2D", "1 0 0\n2 3 4\n3 1 1\n");
+        let problem = load_instance(&path).unwrap();
+        assert!((problem.distance(0, 1) - 5.0).abs() < f64::EPSILON);
+        assert!((problem.distance(1, 0) - 5.0).abs() < f64::EPSILON);
+        assert!((problem.distance(0, 2) - 1.0).abs() < f64::EPSILON);
+        assert!(problem.distance(2, 2).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn rounds_pseudo_euclidean_distances_up() {
+        let path = instance("att", "ATT", "1 0 0\n2 10 0\n");
+        let problem = load_instance(&path).unwrap();
+        // sqrt(10) rounds to 3, below the distance, so it is 4.
+        assert!((problem.distance(0, 1) - 4.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn computes_geographical_distances() {
+        let path = instance(
+            "geo",
+            "GEO",
+            "1 38.24 20.42\n2 39.57 26.15\n3 0.0 0.0\n4 0.0 1.0\n",
+        );
+        let problem = load_instance(&path).unwrap();
+        // First two cities of ulysses16, 509 apart in its distance matrix.
+        assert!((problem.distance(0, 1) - 509.0).abs() < f64::EPSILON);
+        assert!((problem.distance(2, 3) - 112.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn reads_circle24_optimum() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../data/instances/tsp/circle24.tsp");
+        let problem = load_instance(&path).unwrap();
+        assert_eq!(problem.dimension, 24);
+        // Each side of the regular 24-gon of radius 1000 rounds to 261.
+        assert_eq!(problem.optimal_length, Some(24.0 * 261.0));
+
+        let tour = load_tour(path.with_extension("opt.tour"), 24).unwrap();
+        let tsp = Tsp::new(problem);
+        let decoded = tsp.decode(&Individual::Permuted(tour));
+        assert_eq!(tsp.known_optimum(), Some(tsp.objective(&decoded)));
+        let identity: Vec<f64> = (0..24).map(f64::from).collect();
+        assert!(tsp.objective(&identity) > tsp.objective(&decoded));
+    }
+
+    #[test]
+    fn rejects_dimension_below_two() {
+        let path = instance("single", "EUC_2D", "1 0 0\n");
+        assert!(matches!(
+            load_instance(&path),
+            Err(ProblemError::Parse {
+                line: 3,
+                column: 13,
+                ..
+            })
+        ));
+        let path = instance("empty", "EUC_2D", "");
+        assert!(matches!(
+            load_instance(&path),
+            Err(ProblemError::Parse {
+                line: 3,
+                column: 13,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_tour() {
+        let path = test_instance(
+            "invalid.opt.tour",
+            "TYPE : TOUR\nTOUR_SECTION\n1\n2\n2\n-1\nEOF\n",
+        );
+        assert!(matches!(
+            load_tour(&path, 3),
+            Err(ProblemError::Parse { line: 5, .. })
+        ));
+        assert!(matches!(
+            load_tour(&path, 2),
+            Err(ProblemError::Parse { line: 5, .. })
+        ));
+        let path = test_instance("short.opt.tour", "TOUR_SECTION\n1\n2\n-1\n");
+        assert!(matches!(
+            load_tour(&path, 3),
+            Err(ProblemError::Parse { .. })
+        ));
+        assert_eq!(load_tour(&path, 2).unwrap(), vec![0, 1]);
+    }
+}
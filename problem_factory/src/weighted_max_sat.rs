@@ -0,0 +1,285 @@
+use std::path::Path;
+
+use loader_config::PopType;
+use population::Individual;
+
+use crate::{
+    dimacs::{check_clause_count, header_values, literals, read_dimacs},
+    error::ProblemError,
+    reader::parse_error,
+    sat_3::SAT3,
+    Problem,
+};
+
+/// Weighted CNF formula read from a WCNF file. Clauses weighing at least
+/// `top` are hard, the others are soft.
+pub struct Wcnf {
+    pub variables: usize,
+    pub top: u64,
+    pub clauses: Vec<(u64, Vec<i32>)>,
+}
+
+pub struct WeightedMaxSat {
+    variables: usize,
+    hard_clauses: Vec<Vec<(usize, bool)>>,
+    soft_clauses: Vec<(f64, Vec<(usize, bool)>)>,
+    soft_weight: f64,
+}
+
+impl WeightedMaxSat {
+    pub fn new(problem: &Wcnf) -> WeightedMaxSat {
+        let mut hard_clauses = Vec::new();
+        let mut soft_clauses = Vec::new();
+        for (weight, literals) in &problem.clauses {
+            let clause = SAT3::clause(literals);
+            if *weight >= problem.top {
+                hard_clauses.push(clause);
+            } else {
+                soft_clauses.push((*weight as f64, clause));
+            }
+        }
+        let soft_weight = soft_clauses.iter().map(|(weight, _)| weight).sum();
+        WeightedMaxSat {
+            variables: problem.variables,
+            hard_clauses,
+            soft_clauses,
+            soft_weight,
+        }
+    }
+}
+
+impl Problem for WeightedMaxSat {
+    fn decode(&self, individual: &Individual) -> Vec<f64> {
+        match &individual {
+            Individual::Binary(value) => {
+                value.iter().map(|&v| f64::from(u32::from(v)))
+            }
+            Individual::Permuted(_)
+            | Individual::Real(_)
+            | Individual::Integer(_) => todo!(),
+        }
+        .collect()
+    }
+
+    fn normed_objective(&self, individual: &[f64]) -> f64 {
+        if self.soft_weight == 0.0 {
+            return 1.0;
+        }
+        self.objective(individual) / self.soft_weight
+    }
+
+    fn supports(&self, pop_type: PopType) -> bool {
+        matches!(pop_type, PopType::Binary)
+    }
+
+    fn dimension(&self, _pop_type: PopType) -> Option<usize> {
+        Some(self.variables)
+    }
+
+    /// Fraction of hard clauses left unsatisfied.
+    fn constraint(&self, individual: &[f64]) -> f64 {
+        if self.hard_clauses.is_empty() {
+            return 0.0;
+        }
+        let violated = self
+            .hard_clauses
+            .iter()
+            .filter(|clause| !SAT3::eval_solution(individual, clause))
+            .count();
+        violated as f64 / self.hard_clauses.len() as f64
+    }
+
+    /// Total weight of the satisfied soft clauses.
+    fn objective(&self, individual: &[f64]) -> f64 {
+        self.soft_clauses
+            .iter()
+            .filter(|(_, clause)| SAT3::eval_solution(individual, clause))
+            .map(|(weight, _)| weight)
+            .sum()
+    }
+
+    fn get_name(&self) -> String {
+        String::from("WEIGHTED-MAX-SAT")
+    }
+
+    /// Satisfies the violated hard clauses one after the other, flipping
+    /// the variable that leaves the fewest of the others violated.
+    fn repair(&self, individual: &mut Individual) {
+        let Individual::Binary(assignment) = individual else {
+            return;
+        };
+        let satisfied = |assignment: &[bool], clause: &[(usize, bool)]| {
+            clause
+                .iter()
+                .any(|&(variable, negated)| assignment[variable] != negated)
+        };
+        for clause in &self.hard_clauses {
+            if satisfied(assignment, clause) {
+                continue;
+            }
+            let violated = |variable: usize, assignment: &mut [bool]| {
+                assignment[variable] = !assignment[variable];
+                let violated = self
+                    .hard_clauses
+                    .iter()
+                    .filter(|clause| !satisfied(assignment, clause))
+                    .count();
+                assignment[variable] = !assignment[variable];
+                violated
+            };
+            if let Some(variable) = clause
+                .iter()
+                .map(|&(variable, _)| variable)
+                .min_by_key(|&variable| violated(variable, assignment))
+            {
+                assignment[variable] = !assignment[variable];
+            }
+        }
+    }
+}
+
+/// Reads a DIMACS WCNF file with an optional
+/// `p wcnf <variables> <clauses> [<top>]` header, every clause starting
+/// with its weight. Without a top weight every clause is soft.
+///
+/// # Errors
+/// If the file cannot be read, a token is not a weight or literal or the
+/// formula does not match its header
+pub fn load_instance<P>(filename: P) -> Result<Wcnf, ProblemError>
+where
+    P: AsRef<Path>,
+{
+    let path = filename.as_ref();
+    let dimacs = read_dimacs(path, "wcnf")?;
+    let header = dimacs
+        .header
+        .map(|header| {
+            header_values(
+                path,
+                &header,
+                "p wcnf <variables> <clauses> [<top>]",
+                2..=3,
+            )
+        })
+        .transpose()?;
+    let declared_variables = header.as_ref().map(|header| header[0].2 as usize);
+    let top = header
+        .as_ref()
+        .and_then(|header| header.get(2))
+        .map_or(u64::MAX, |&(_, _, top)| top);
+    let clauses = dimacs
+        .clauses
+        .iter()
+        .map(|(end_line, clause)| {
+            let Some((&(line, column, weight), clause)) = clause.split_first()
+            else {
+                return Err(ProblemError::MissingField {
+                    path: path.to_path_buf(),
+                    line: *end_line,
+                    field: "clause weight",
+                });
+            };
+            let weight = u64::try_from(weight).map_err(|error| {
+                parse_error(
+                    path,
+                    line,
+                    column,
+                    &weight.to_string(),
+                    error.to_string(),
+                )
+            })?;
+            Ok((weight, literals(path, clause, declared_variables)?))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if let Some(header) = &header {
+        check_clause_count(path, header[1], clauses.len())?;
+    }
+    let variables = declared_variables.unwrap_or_else(|| {
+        clauses
+            .iter()
+            .flat_map(|(_, clause)| clause)
+            .map(|literal| literal.unsigned_abs() as usize)
+            .max()
+            .unwrap_or(0)
+    });
+    Ok(Wcnf {
+        variables,
+        top,
+        clauses,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::reader::test_instance;
+
+    #[test]
+    fn splits_hard_and_soft_clauses_at_top() {
+        let path = test_instance(
+            "top.wcnf",
+            "p wcnf 3 4 10\n10 1 2 0\n11 3 0\n9 2 3 0\n1 -1 0\n",
+        );
+        let wcnf = load_instance(&path).unwrap();
+        assert_eq!(wcnf.top, 10);
+        let problem = WeightedMaxSat::new(&wcnf);
+        assert_eq!(problem.hard_clauses.len(), 2);
+        assert_eq!(problem.soft_clauses.len(), 2);
+        assert!((problem.soft_weight - 10.0).abs() < f64::EPSILON);
+
+        // x1 = x2 = false, x3 = true violates the first hard clause and
+        // satisfies both soft ones.
+        let decoded = [0.0, 0.0, 1.0];
+        assert!((problem.constraint(&decoded) - 0.5).abs() < f64::EPSILON);
+        assert!((problem.objective(&decoded) - 10.0).abs() < f64::EPSILON);
+        assert!(!problem.is_feasible(&decoded));
+    }
+
+    #[test]
+    fn treats_every_clause_as_soft_without_top() {
+        let path =
+            test_instance("no-top.wcnf", "p wcnf 2 2\n1000 1 0\n3 -2 0\n");
+        let wcnf = load_instance(&path).unwrap();
+        assert_eq!(wcnf.top, u64::MAX);
+        let problem = WeightedMaxSat::new(&wcnf);
+        assert!(problem.hard_clauses.is_empty());
+        assert!(problem.is_feasible(&[0.0, 0.0]));
+    }
+
+    #[test]
+    fn rejects_clause_count_mismatch() {
+        let path = test_instance("count.wcnf", "p wcnf 2 1 5\n5 1 0\n1 -2 0\n");
+        let Err(error) = load_instance(&path) else {
+            panic!("clause count mismatch accepted");
+        };
+        assert!(matches!(error, ProblemError::Parse { line: 1, .. }));
+        assert!(error
+            .to_string()
+            .contains("header declares 1 clauses, found 2"));
+    }
+
+    #[test]
+    fn rejects_negative_weight() {
+        let path = test_instance("weight.wcnf", "p wcnf 2 1 5\n-5 1 0\n");
+        assert!(matches!(
+            load_instance(&path),
+            Err(ProblemError::Parse {
+                line: 2,
+                column: 1,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn loads_bundled_instance() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../data/instances/weighted-max-sat/uf100-01.wcnf");
+        let problem = WeightedMaxSat::new(&load_instance(path).unwrap());
+        assert_eq!(problem.variables, 100);
+        assert_eq!(problem.hard_clauses.len(), 30);
+        assert_eq!(problem.soft_clauses.len(), 400);
+    }
+}